@@ -0,0 +1,614 @@
+use crate::{
+    Array, Data, Date, Dictionary, Error, Null, PString, PlistPath, Uid, UidAllocator, Value,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A problem found in an `NSKeyedArchiver` object graph by [Value::validate_archive].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// The root of the archive isn't a dictionary.
+    NotADictionary,
+    /// The `$objects` key is missing.
+    MissingObjects,
+    /// `$objects` isn't an array.
+    ObjectsNotArray,
+    /// The `$top` key is missing.
+    MissingTop,
+    /// `$top` isn't a dictionary.
+    TopNotDictionary,
+    /// A uid references an object that doesn't exist in `$objects`.
+    UidOutOfRange {
+        /// The location of the uid.
+        path: PlistPath,
+        /// The value of the uid.
+        uid: u64,
+        /// The number of objects in `$objects`.
+        objects: u32,
+    },
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::NotADictionary => f.write_str("The archive root isn't a dictionary"),
+            ArchiveError::MissingObjects => f.write_str("The archive has no `$objects` key"),
+            ArchiveError::ObjectsNotArray => f.write_str("`$objects` isn't an array"),
+            ArchiveError::MissingTop => f.write_str("The archive has no `$top` key"),
+            ArchiveError::TopNotDictionary => f.write_str("`$top` isn't a dictionary"),
+            ArchiveError::UidOutOfRange { path, uid, objects } => write!(
+                f,
+                "The uid {uid} at `/{path}` is out of range (there are {objects} objects)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl Value<'_> {
+    /// Checks that the value is a structurally sound `NSKeyedArchiver` archive.
+    ///
+    /// `$objects` must be an array, `$top` must be a dictionary, and every uid
+    /// found in either of them must reference an existing object. The first
+    /// problem found is returned.
+    pub fn validate_archive(&self) -> Result<(), ArchiveError> {
+        let root = self.as_dictionary().ok_or(ArchiveError::NotADictionary)?;
+        let objects = root.get("$objects").ok_or(ArchiveError::MissingObjects)?;
+        let count = objects
+            .as_array()
+            .ok_or(ArchiveError::ObjectsNotArray)?
+            .len();
+        let top = root.get("$top").ok_or(ArchiveError::MissingTop)?;
+        if top.as_dictionary().is_none() {
+            return Err(ArchiveError::TopNotDictionary);
+        }
+
+        let mut path = PlistPath::new();
+        path.push("$top");
+        check_uids(&top, count, &mut path)?;
+        path.pop();
+        path.push("$objects");
+        check_uids(&objects, count, &mut path)
+    }
+
+    /// Encodes a dictionary into an `NSKeyedArchiver` archive, the inverse
+    /// of [Value::nskeyed_decode].
+    ///
+    /// Every entry of the dictionary becomes an entry of `$top`, so an archive
+    /// of a single root object is made with `dict!("root" => value)`.
+    /// The objects are stored as described in [ArchiveBuilder].
+    ///
+    /// Returns [Error::Format] if the value isn't a dictionary or the tree
+    /// contains uid or key nodes.
+    pub fn nskeyed_encode<'b>(&self) -> Result<Value<'b>, Error> {
+        let top = self.as_dictionary().ok_or(Error::Format)?;
+        let mut builder = ArchiveBuilder::new();
+        for (key, item) in top {
+            builder.insert_top(key, &item)?;
+        }
+        Ok(builder.finish())
+    }
+
+    /// Decodes an `NSKeyedArchiver` archive into a plain plist tree.
+    ///
+    /// The uids are followed through `$objects` starting from `$top`, and the
+    /// result is a dictionary of the decoded `$top` entries. The common
+    /// Foundation classes become native nodes:
+    ///
+    /// | Class | Node |
+    /// |-------|------|
+    /// | `NSArray`, `NSSet`, `NSOrderedSet` and their mutable variants | [Array] |
+    /// | `NSDictionary`, `NSMutableDictionary` | [Dictionary] |
+    /// | `NSString`, `NSMutableString` | [PString] |
+    /// | `NSData`, `NSMutableData` | [Data] |
+    /// | `NSDate` | [Date] |
+    ///
+    /// The `$null` object becomes a [Null] node and plain objects are copied.
+    /// Objects of other classes become dictionaries of their decoded fields
+    /// plus a `$classname` entry. An object referenced several times is
+    /// copied every time.
+    ///
+    /// Returns [Error::Format] if the archive isn't valid (see
+    /// [Value::validate_archive]), an object references itself, or an object
+    /// of a known class is malformed, e.g. a dictionary key isn't a string.
+    /// Since shared objects are copied, a crafted archive could expand
+    /// exponentially, so [Error::Format] is returned as well once more than
+    /// 16 objects per node of `$objects` have been decoded.
+    pub fn nskeyed_decode<'b>(&self) -> Result<Value<'b>, Error> {
+        self.validate_archive().map_err(|_| Error::Format)?;
+        let root = self.as_dictionary().ok_or(Error::Format)?;
+        let objects = root.get("$objects").ok_or(Error::Format)?;
+        let top = root.get("$top").ok_or(Error::Format)?;
+        let mut decoder = Decoder {
+            objects: objects.as_array().ok_or(Error::Format)?,
+            visiting: Vec::new(),
+            budget: objects.node_count().saturating_mul(DECODE_EXPANSION),
+        };
+        let mut decoded = Dictionary::new();
+        for (key, item) in top.as_dictionary().ok_or(Error::Format)? {
+            decoded.insert(key, decoder.field(&item)?);
+        }
+        Ok(decoded.into())
+    }
+}
+
+/// Builds an `NSKeyedArchiver` archive object by object.
+///
+/// Every encoded value is appended to `$objects` along with the objects
+/// it references, and its uid is returned. Arrays, dictionaries and dates
+/// are stored as `NSArray`, `NSDictionary` and `NSDate` instances, while
+/// strings, numbers, booleans and data are stored as is, the way
+/// `NSKeyedArchiver` stores `NSString`, `NSNumber` and `NSData`. A [Null]
+/// node is a reference to `$null`. The class descriptions are shared
+/// between all instances of a class.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{ArchiveBuilder, Value, array};
+///
+/// let mut builder = ArchiveBuilder::new();
+/// builder.insert_top("root", &array!("pig", 4).into()).unwrap();
+/// let archive = builder.finish();
+/// assert_eq!(archive.validate_archive(), Ok(()));
+/// assert_eq!(
+///     archive.nskeyed_decode().unwrap(),
+///     plist_plus2::dict!("root" => array!("pig", 4)).into()
+/// );
+/// ```
+#[derive(Debug)]
+pub struct ArchiveBuilder {
+    objects: Array<'static>,
+    top: Dictionary<'static>,
+    uids: UidAllocator,
+    classes: HashMap<&'static str, u64>,
+}
+
+impl ArchiveBuilder {
+    /// Creates an archive containing only the `$null` object.
+    pub fn new() -> Self {
+        let mut builder = Self {
+            objects: Array::new(),
+            top: Dictionary::new(),
+            uids: UidAllocator::new(0),
+            classes: HashMap::new(),
+        };
+        builder.push("$null".into());
+        builder
+    }
+
+    /// Appends the value and everything it contains to `$objects`
+    /// and returns the uid of the value.
+    ///
+    /// Returns [Error::Format] if the tree contains uid or key nodes,
+    /// which can't be archived.
+    pub fn encode<'b>(&mut self, value: &Value) -> Result<Uid<'b>, Error> {
+        match value {
+            Value::Null(_) => return Ok(Uid::new(0)),
+            Value::Uid(_) | Value::Key(_) => return Err(Error::Format),
+            Value::Array(_) | Value::Dictionary(_) | Value::Date(_) => {}
+            _ => return Ok(self.push(value.clone())),
+        }
+        // The instance gets its uid before the objects it references,
+        // like in the archives made by Foundation
+        let uid = self.push(Null::new().into());
+        let (class, mut object) = match value {
+            Value::Array(array) => {
+                let mut uids = Array::new();
+                for item in array {
+                    uids.append(self.encode(&item)?);
+                }
+                let mut object = Dictionary::new();
+                object.insert("NS.objects", uids);
+                ("NSArray", object)
+            }
+            Value::Dictionary(dict) => {
+                let (mut keys, mut uids) = (Array::new(), Array::new());
+                for (key, item) in dict {
+                    keys.append(self.push(key.into()));
+                    uids.append(self.encode(&item)?);
+                }
+                let mut object = Dictionary::new();
+                object.insert("NS.keys", keys);
+                object.insert("NS.objects", uids);
+                ("NSDictionary", object)
+            }
+            Value::Date(date) => {
+                let time = date.unix_micros() as f64 / 1e6 - MAC_EPOCH;
+                let mut object = Dictionary::new();
+                object.insert("NS.time", time);
+                ("NSDate", object)
+            }
+            _ => unreachable!("only instances get here"),
+        };
+        object.insert("$class", self.class(class));
+        self.objects.set(object, uid.get() as u32);
+        Ok(uid)
+    }
+
+    /// Encodes the value and stores its uid in `$top` under the key.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the supplied string contains an internal 0 byte.
+    pub fn insert_top(&mut self, key: impl Into<String>, value: &Value) -> Result<(), Error> {
+        let uid = self.encode(value)?;
+        self.top.insert(key, uid);
+        Ok(())
+    }
+
+    /// Returns the archive dictionary.
+    pub fn finish<'b>(self) -> Value<'b> {
+        let mut archive = Dictionary::new();
+        archive.insert("$version", 100000);
+        archive.insert("$archiver", "NSKeyedArchiver");
+        archive.insert("$top", self.top);
+        archive.insert("$objects", self.objects);
+        archive.into()
+    }
+
+    /// Appends an object and returns its uid.
+    fn push<'b>(&mut self, object: Value) -> Uid<'b> {
+        self.objects.append(object);
+        self.uids.next()
+    }
+
+    /// Returns the uid of the class description, appending it the first time.
+    fn class<'b>(&mut self, classname: &'static str) -> Uid<'b> {
+        if let Some(&uid) = self.classes.get(classname) {
+            return Uid::new(uid);
+        }
+        let mut class = Dictionary::new();
+        class.insert("$classname", classname);
+        class.insert("$classes", crate::array!(classname, "NSObject"));
+        let uid = self.push(class.into());
+        self.classes.insert(classname, uid.get());
+        uid
+    }
+}
+
+impl Default for ArchiveBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How many objects may be decoded per node of `$objects`.
+const DECODE_EXPANSION: u64 = 16;
+
+/// Resolves the objects of an archive, keeping track of the objects being
+/// decoded to detect cycles.
+struct Decoder<'a, 'b> {
+    objects: &'a Array<'b>,
+    visiting: Vec<u64>,
+    // The number of objects that can still be decoded, which stops
+    // archives of shared objects from expanding exponentially
+    budget: u64,
+}
+
+impl Decoder<'_, '_> {
+    fn object<'c>(&mut self, uid: u64) -> Result<Value<'c>, Error> {
+        if uid == 0 {
+            return Ok(Null::new().into());
+        }
+        if self.visiting.contains(&uid) || self.budget == 0 {
+            return Err(Error::Format);
+        }
+        self.budget -= 1;
+        let object = self.objects.get(uid as u32).ok_or(Error::Format)?;
+        self.visiting.push(uid);
+        let decoded = match &*object {
+            Value::Dictionary(dict) => self.instance(dict),
+            _ => self.field(&object),
+        };
+        self.visiting.pop();
+        decoded
+    }
+
+    /// Decodes a value stored in an object, following the uids.
+    fn field<'c>(&mut self, value: &Value) -> Result<Value<'c>, Error> {
+        Ok(match value {
+            Value::Uid(uid) => self.object(uid.get())?,
+            Value::Array(array) => self.list(array)?.into(),
+            Value::Dictionary(dict) => self.entries(dict)?.into(),
+            _ => value.clone(),
+        })
+    }
+
+    fn entries<'c>(&mut self, dict: &Dictionary) -> Result<Dictionary<'c>, Error> {
+        let mut decoded = Dictionary::new();
+        for (key, item) in dict {
+            decoded.insert(key, self.field(&item)?);
+        }
+        Ok(decoded)
+    }
+
+    fn list<'c>(&mut self, array: &Array) -> Result<Array<'c>, Error> {
+        let mut decoded = Array::new();
+        for item in array {
+            decoded.append(self.field(&item)?);
+        }
+        Ok(decoded)
+    }
+
+    /// Decodes a dictionary object according to its class.
+    fn instance<'c>(&mut self, dict: &Dictionary) -> Result<Value<'c>, Error> {
+        let Some(class) = dict.get("$class") else {
+            return Ok(self.entries(dict)?.into());
+        };
+        let Value::Uid(class) = &*class else {
+            return Err(Error::Format);
+        };
+        let class = self.objects.get(class.get() as u32).ok_or(Error::Format)?;
+        let classname = class
+            .as_dictionary()
+            .and_then(|class| class.get("$classname"))
+            .ok_or(Error::Format)?;
+        let classname = classname.as_string().ok_or(Error::Format)?.try_as_str()?;
+        let field = |key: &str| dict.get(key).ok_or(Error::Format);
+        Ok(match classname {
+            "NSArray"
+            | "NSMutableArray"
+            | "NSSet"
+            | "NSMutableSet"
+            | "NSOrderedSet"
+            | "NSMutableOrderedSet" => {
+                let objects = field("NS.objects")?;
+                self.list(objects.as_array().ok_or(Error::Format)?)?.into()
+            }
+            "NSDictionary" | "NSMutableDictionary" => {
+                let keys = field("NS.keys")?;
+                let keys = keys.as_array().ok_or(Error::Format)?;
+                let objects = field("NS.objects")?;
+                let objects = objects.as_array().ok_or(Error::Format)?;
+                if keys.len() != objects.len() {
+                    return Err(Error::Format);
+                }
+                let mut decoded = Dictionary::new();
+                for (key, object) in keys.iter().zip(objects.iter()) {
+                    let key = self.field(&key)?;
+                    let key = key.as_string().ok_or(Error::Format)?.try_as_str()?;
+                    if key.contains('\0') {
+                        return Err(Error::Format);
+                    }
+                    decoded.insert(key, self.field(&object)?);
+                }
+                decoded.into()
+            }
+            "NSString" | "NSMutableString" => {
+                let string = field("NS.string").map(|string| self.field(&string));
+                match string {
+                    Ok(string) => string?,
+                    // Some archivers store the string as UTF-8 bytes
+                    Err(_) => {
+                        let bytes = field("NS.bytes")?;
+                        let bytes = bytes.as_data().ok_or(Error::Format)?.as_bytes();
+                        let string = std::str::from_utf8(bytes).map_err(|_| Error::Format)?;
+                        PString::try_new(string).map_err(|_| Error::Format)?.into()
+                    }
+                }
+            }
+            "NSData" | "NSMutableData" => {
+                let data = field("NS.data")?;
+                let data = self.field(&data)?;
+                Data::new(data.as_data().ok_or(Error::Format)?.as_bytes()).into()
+            }
+            "NSDate" => {
+                let time = field("NS.time")?;
+                let time = time.as_real().ok_or(Error::Format)?.as_float();
+                let micros = ((time + MAC_EPOCH) * 1e6).round();
+                if !micros.is_finite() {
+                    return Err(Error::Format);
+                }
+                Date::from_unix_micros(micros as i64)
+                    .ok_or(Error::Format)?
+                    .into()
+            }
+            _ => {
+                let mut decoded = Dictionary::new();
+                decoded.insert("$classname", classname);
+                for (key, item) in dict.iter().filter(|(key, _)| key != "$class") {
+                    decoded.insert(key, self.field(&item)?);
+                }
+                decoded.into()
+            }
+        })
+    }
+}
+
+/// The seconds between the Unix Epoch and the `NSDate` reference date,
+/// 1 January 2001.
+const MAC_EPOCH: f64 = 978307200.0;
+
+fn check_uids(value: &Value, objects: u32, path: &mut PlistPath) -> Result<(), ArchiveError> {
+    match value {
+        Value::Uid(uid) if uid.get() >= objects as u64 => Err(ArchiveError::UidOutOfRange {
+            path: path.clone(),
+            uid: uid.get(),
+            objects,
+        }),
+        Value::Array(array) => {
+            for (i, item) in array.iter().enumerate() {
+                path.push(i as u32);
+                check_uids(&item, objects, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        Value::Dictionary(dict) => {
+            for (key, item) in dict.iter() {
+                path.push(key);
+                check_uids(&item, objects, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::time::Duration;
+
+    #[test]
+    fn validate_archive() {
+        let mut plist = from_file("./tests/binary_NSKeyedArchiver.plist").unwrap();
+        assert_eq!(plist.validate_archive(), Ok(()));
+
+        plist
+            .get_mut_path("$objects/1/$class")
+            .unwrap()
+            .as_uid_mut()
+            .unwrap()
+            .set(42);
+        let error = plist.validate_archive().unwrap_err();
+        assert_eq!(
+            error,
+            ArchiveError::UidOutOfRange {
+                path: PlistPath::from(vec!["$objects".into(), 1.into(), "$class".into()]),
+                uid: 42,
+                objects: 5,
+            }
+        );
+
+        let plist: Value = dict!("$top" => dict!()).into();
+        assert_eq!(plist.validate_archive(), Err(ArchiveError::MissingObjects));
+        let plist: Value = array!().into();
+        assert_eq!(plist.validate_archive(), Err(ArchiveError::NotADictionary));
+    }
+
+    #[test]
+    fn nskeyed_decode() {
+        let plist = from_file("./tests/binary_NSKeyedArchiver.plist").unwrap();
+        let decoded = plist.nskeyed_decode().unwrap();
+        let items = decoded.get_path(&path!["foundItems"]).unwrap();
+        let items = items.as_dictionary().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items["$classname"], "NSMutableIndexSet".into());
+        assert_eq!(items["NSRangeCount"], 42.into());
+        assert_eq!(items["NSRangeData"].as_data().unwrap().len(), 103);
+    }
+
+    #[test]
+    fn nskeyed_decode_foundation() {
+        let class =
+            |name: &str| dict!("$classname" => name, "$classes" => array!(name, "NSObject"));
+        let plist: Value = dict!(
+            "$version" => 100000,
+            "$archiver" => "NSKeyedArchiver",
+            "$top" => dict!("root" => Uid::new(1)),
+            "$objects" => array!(
+                "$null",
+                dict!(
+                    "NS.keys" => array!(Uid::new(2), Uid::new(3), Uid::new(4)),
+                    "NS.objects" => array!(Uid::new(5), Uid::new(7), Uid::new(0)),
+                    "$class" => Uid::new(6)
+                ),
+                "names",
+                "when",
+                "nothing",
+                dict!("NS.objects" => array!(Uid::new(8), 5), "$class" => Uid::new(9)),
+                class("NSMutableDictionary"),
+                dict!("NS.time" => 0.5, "$class" => Uid::new(10)),
+                dict!("NS.string" => "pig", "$class" => Uid::new(11)),
+                class("NSArray"),
+                class("NSDate"),
+                class("NSMutableString")
+            )
+        )
+        .into();
+        let decoded = plist.nskeyed_decode().unwrap();
+        let expected: Value = dict!(
+            "root" => dict!(
+                "names" => array!("pig", 5),
+                "when" => Duration::from_micros(978307200500000),
+                "nothing" => Null::new()
+            )
+        )
+        .into();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn nskeyed_decode_errors() {
+        let archive = |objects: Array<'static>| -> Value<'static> {
+            dict!("$top" => dict!("root" => Uid::new(1)), "$objects" => objects).into()
+        };
+        let cyclic = archive(array!("$null", dict!("next" => Uid::new(1))));
+        assert_eq!(cyclic.nskeyed_decode().unwrap_err(), Error::Format);
+        let out_of_range = archive(array!("$null", Uid::new(5)));
+        assert_eq!(out_of_range.nskeyed_decode().unwrap_err(), Error::Format);
+        let no_keys = archive(array!(
+            "$null",
+            dict!("$class" => Uid::new(2)),
+            dict!("$classname" => "NSDictionary")
+        ));
+        assert_eq!(no_keys.nskeyed_decode().unwrap_err(), Error::Format);
+
+        // Every object references the next one twice, which would decode
+        // into 2^40 leaves
+        let mut diamonds = array!("$null");
+        for uid in 2..=41 {
+            diamonds.append(array!(Uid::new(uid), Uid::new(uid)));
+        }
+        diamonds.append("leaf");
+        assert_eq!(
+            archive(diamonds).nskeyed_decode().unwrap_err(),
+            Error::Format
+        );
+        // A leaf shared by many references is fine
+        let references: Array = (0..64).map(|_| Value::from(Uid::new(2))).collect();
+        let shared = archive(array!("$null", references, "leaf"));
+        assert!(shared.nskeyed_decode().is_ok());
+    }
+
+    #[test]
+    fn nskeyed_encode_round_trip() {
+        let plist: Value = dict!(
+            "root" => dict!(
+                "names" => array!("pig", 5, true, 1.5, array!()),
+                "when" => Duration::from_micros(1546635600500000),
+                "bytes" => vec![1u8, 2, 3],
+                "nothing" => Null::new(),
+                "nested" => dict!("empty" => dict!())
+            ),
+            "count" => 2
+        )
+        .into();
+        let archive = plist.nskeyed_encode().unwrap();
+        assert_eq!(archive.validate_archive(), Ok(()));
+        assert_eq!(
+            *archive.get_path(&path!["$objects", 0]).unwrap(),
+            "$null".into()
+        );
+        assert_eq!(
+            *archive.get_path(&path!["$top", "root"]).unwrap(),
+            Uid::new(1).into()
+        );
+        assert_eq!(archive.nskeyed_decode().unwrap(), plist);
+
+        // The class descriptions are shared
+        let archive = Value::from(dict!("a" => array!(array!(), array!()))).nskeyed_encode();
+        let objects = archive
+            .unwrap()
+            .get_path(&path!["$objects"])
+            .unwrap()
+            .clone();
+        let classes = objects.as_array().unwrap().iter();
+        let classes = classes.filter(|object| {
+            object
+                .as_dictionary()
+                .is_some_and(|object| object.get("$classname").is_some())
+        });
+        assert_eq!(classes.count(), 1);
+
+        assert_eq!(
+            Value::from(array!()).nskeyed_encode().unwrap_err(),
+            Error::Format
+        );
+        let uid: Value = dict!("a" => Uid::new(1)).into();
+        assert_eq!(uid.nskeyed_encode().unwrap_err(), Error::Format);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{Error, Value, ValueType, path};
+
+/// A set of rules that convert string leaves of a tree into other types,
+/// applied with [Value::apply_coercions].
+///
+/// Every rule is a pattern in the [string path](crate::PlistPath#string-paths)
+/// syntax, where a `*` segment matches any single key or index and a `**`
+/// segment matches any number of them, including none. The first matching
+/// rule wins. The conversion itself is done by [Value::coerce].
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{CoercionTable, Value, ValueType, dict};
+///
+/// let mut table = CoercionTable::new();
+/// table.add("**/port", ValueType::Integer).unwrap();
+/// table.add("*/enabled", ValueType::Boolean).unwrap();
+///
+/// let mut config: Value = dict!("server" => dict!("port" => "8080", "enabled" => "yes")).into();
+/// assert_eq!(config.apply_coercions(&table), 2);
+/// assert_eq!(config, dict!("server" => dict!("port" => 8080, "enabled" => true)).into());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoercionTable {
+    rules: Vec<(Vec<String>, ValueType)>,
+}
+
+impl CoercionTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule that coerces the strings matching the pattern into
+    /// the `target` type.
+    ///
+    /// Returns [Error::InvalidArg] if the pattern is malformed or empty,
+    /// since the root itself can't be coerced.
+    pub fn add(&mut self, pattern: &str, target: ValueType) -> Result<&mut Self, Error> {
+        let segments = path::parse_segments(pattern)?;
+        if segments.is_empty() {
+            return Err(Error::InvalidArg);
+        }
+        self.rules.push((segments, target));
+        Ok(self)
+    }
+
+    /// Returns the target type of the first rule matching the path.
+    fn target(&self, path: &[String]) -> Option<ValueType> {
+        self.rules
+            .iter()
+            .find(|(pattern, _)| matches(pattern, path))
+            .map(|(_, target)| *target)
+    }
+}
+
+fn matches(pattern: &[String], path: &[String]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, None) => true,
+        (Some((any, rest)), _) if any == "**" => {
+            (0..=path.len()).any(|skipped| matches(rest, &path[skipped..]))
+        }
+        (Some((segment, rest)), Some((key, path))) => {
+            (segment == "*" || segment == key) && matches(rest, path)
+        }
+        _ => false,
+    }
+}
+
+impl Value<'_> {
+    /// Converts every string leaf matching a rule of the table
+    /// into the rule's type and returns the number of converted leaves.
+    ///
+    /// Strings that can't be converted, e.g. `"abc"` into an integer,
+    /// are left untouched.
+    pub fn apply_coercions(&mut self, table: &CoercionTable) -> usize {
+        coerce_children(self, table, &mut Vec::new())
+    }
+}
+
+fn coerce_children(value: &mut Value, table: &CoercionTable, path: &mut Vec<String>) -> usize {
+    let mut count = 0;
+    match value {
+        Value::Array(array) => {
+            for index in 0..array.len() {
+                path.push(index.to_string());
+                let coerced = coerce_leaf(&array.get(index).unwrap(), table, path);
+                match coerced {
+                    Some(coerced) => {
+                        array.set(coerced, index);
+                        count += 1;
+                    }
+                    None => {
+                        count += coerce_children(&mut array.get_mut(index).unwrap(), table, path)
+                    }
+                }
+                path.pop();
+            }
+        }
+        Value::Dictionary(dict) => {
+            let keys: Vec<String> = dict.iter().map(|(key, _)| key).collect();
+            for key in keys {
+                path.push(key);
+                let key = path.last().unwrap().as_str();
+                let coerced = coerce_leaf(&dict.get(key).unwrap(), table, path);
+                match coerced {
+                    Some(coerced) => {
+                        dict.insert(key, coerced);
+                        count += 1;
+                    }
+                    None => count += coerce_children(&mut dict.get_mut(key).unwrap(), table, path),
+                }
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    count
+}
+
+fn coerce_leaf<'b>(value: &Value, table: &CoercionTable, path: &[String]) -> Option<Value<'b>> {
+    let Value::PString(_) = value else {
+        return None;
+    };
+    let target = table.target(path)?;
+    if target == ValueType::String {
+        return None;
+    }
+    value.coerce(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn coercion_table() {
+        let mut table = CoercionTable::new();
+        table
+            .add("**/port", ValueType::Integer)
+            .unwrap()
+            .add("servers/*/weight", ValueType::Real)
+            .unwrap();
+        let mut config: Value = dict!(
+            "port" => "8080",
+            "servers" => array!(
+                dict!("port" => "80", "weight" => "0.5"),
+                dict!("port" => "http", "weight" => 1)
+            ),
+            "name" => "8080"
+        )
+        .into();
+        assert_eq!(config.apply_coercions(&table), 3);
+        let expected: Value = dict!(
+            "port" => 8080,
+            "servers" => array!(
+                dict!("port" => 80, "weight" => 0.5),
+                dict!("port" => "http", "weight" => 1)
+            ),
+            "name" => "8080"
+        )
+        .into();
+        assert_eq!(config, expected);
+
+        assert_eq!(
+            table.add("", ValueType::Integer).unwrap_err(),
+            Error::InvalidArg
+        );
+        assert_eq!(
+            table.add("a//b", ValueType::Integer).unwrap_err(),
+            Error::InvalidArg
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{Error, PLIST_ERROR_SUCCESS, PlistFormat, Value, format, unsafe_bindings};
+
+/// A parsed plist that remembers the format it was read from.
+///
+/// This is handy for editors: open a file, modify its contents and
+/// [save](Document::save) it back without changing the format.
+///
+/// ```rust,no_run
+/// use plist_plus2::Document;
+///
+/// let mut doc = Document::from_file_with_format("Info.plist").unwrap();
+/// doc.value_mut()
+///     .as_dictionary_mut()
+///     .unwrap()
+///     .insert("CFBundleVersion", "2");
+/// doc.save("Info.plist").unwrap();
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Document<'a> {
+    value: Value<'a>,
+    format: PlistFormat,
+}
+
+impl<'a> Document<'a> {
+    /// Parses a slice of bytes, determines its plist format and returns
+    /// a [Document] holding both.
+    pub fn from_memory_with_format(bytes: &[u8]) -> Result<Self, Error> {
+        let mut plist_t = unsafe { std::mem::zeroed() };
+        let mut raw_format = unsafe_bindings::plist_format_t_PLIST_FORMAT_NONE;
+        let result = unsafe {
+            unsafe_bindings::plist_from_memory(
+                bytes.as_ptr() as *mut _,
+                bytes.len() as u32,
+                &mut plist_t,
+                &mut raw_format,
+            )
+        };
+        if result != PLIST_ERROR_SUCCESS {
+            return Err(result.into());
+        }
+        let value = unsafe { crate::from_pointer(plist_t) };
+        let format = PlistFormat::from_raw(raw_format).ok_or(Error::Unknown)?;
+        Ok(Self { value, format })
+    }
+
+    /// Reads a file, determines its plist format and returns
+    /// a [Document] holding both.
+    pub fn from_file_with_format(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let bytes = std::fs::read(path).map_err(|_| Error::IO)?;
+        Self::from_memory_with_format(&bytes)
+    }
+
+    /// Creates a document from a value and the format it should be saved in.
+    pub fn new(value: Value<'a>, format: PlistFormat) -> Self {
+        Self { value, format }
+    }
+
+    /// Returns the format the document was parsed from.
+    pub fn format(&self) -> PlistFormat {
+        self.format
+    }
+
+    /// Changes the format used by [Document::save].
+    pub fn set_format(&mut self, format: PlistFormat) {
+        self.format = format;
+    }
+
+    /// Returns an immutable reference to the root value.
+    pub fn value(&self) -> &Value<'a> {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the root value.
+    pub fn value_mut(&mut self) -> &mut Value<'a> {
+        &mut self.value
+    }
+
+    /// Consumes the document and returns the root value.
+    pub fn into_value(self) -> Value<'a> {
+        self.value
+    }
+
+    /// Serializes the root value in the document format.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        format::export(&self.value, self.format)
+    }
+
+    /// Writes the document to a file in the same format it was parsed from.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        let bytes = self.to_bytes()?;
+        std::fs::write(path, bytes).map_err(|_| Error::IO)
+    }
+}
+
+/// A plist file opened from disk, saved back to the same path
+/// in the format it was read from.
+///
+/// This is a [Document] that remembers its path, which covers the common
+/// "load, modify, save" cycle of preference files without turning
+/// a binary plist into an XML one.
+///
+/// ```rust,no_run
+/// use plist_plus2::PlistFile;
+///
+/// let mut file = PlistFile::open("com.example.app.plist").unwrap();
+/// file.value_mut()
+///     .as_dictionary_mut()
+///     .unwrap()
+///     .insert("LaunchCount", 2);
+/// file.save().unwrap();
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct PlistFile<'a> {
+    path: std::path::PathBuf,
+    document: Document<'a>,
+}
+
+impl<'a> PlistFile<'a> {
+    /// Reads and parses a file, remembering its path and plist format.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let document = Document::from_file_with_format(&path)?;
+        Ok(Self { path, document })
+    }
+
+    /// Returns the path the file was opened from.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// Returns the format the file was parsed from.
+    pub fn format(&self) -> PlistFormat {
+        self.document.format()
+    }
+
+    /// Returns an immutable reference to the root value.
+    pub fn value(&self) -> &Value<'a> {
+        self.document.value()
+    }
+
+    /// Returns a mutable reference to the root value.
+    pub fn value_mut(&mut self) -> &mut Value<'a> {
+        self.document.value_mut()
+    }
+
+    /// Consumes the file and returns the underlying [Document].
+    pub fn into_document(self) -> Document<'a> {
+        self.document
+    }
+
+    /// Writes the root value back to the file in its original format.
+    pub fn save(&self) -> Result<(), Error> {
+        self.document.save(&self.path)
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{Dictionary, Item, Value};
+
+/// Options for a configurable comparison of values with [Value::eq_with].
+///
+/// The default options behave like `==`.
+///
+/// ```rust
+/// use plist_plus2::{EqOptions, Value, array, dict};
+///
+/// let a: Value = dict!("list" => array!(1, 2), "id" => 5).into();
+/// let b: Value = dict!("list" => array!(2, 1), "id" => 6).into();
+/// let opts = EqOptions {
+///     unordered_arrays: true,
+///     ignore_keys: vec!["id".to_string()],
+///     ..Default::default()
+/// };
+/// assert!(a.eq_with(&b, &opts));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EqOptions {
+    /// When `false`, integers and reals are compared by their numeric value
+    /// (so `1` equals `1.0`) and keys are compared with strings.
+    pub type_strict: bool,
+    /// The maximum absolute difference between two reals that are
+    /// considered equal.
+    pub float_tolerance: f64,
+    /// Treats dictionary entries with a [Null](crate::Null) value as missing.
+    pub null_as_absent: bool,
+    /// Dictionary keys that are skipped at any depth.
+    pub ignore_keys: Vec<String>,
+    /// Compares arrays regardless of the order of their items.
+    pub unordered_arrays: bool,
+    /// Compares integers by their numeric value, taking the sign into
+    /// account, so `-1` and [u64::MAX] are different.
+    /// See [Value::eq_strict_integers].
+    pub strict_integers: bool,
+}
+
+impl Default for EqOptions {
+    fn default() -> Self {
+        Self {
+            type_strict: true,
+            float_tolerance: 0.0,
+            null_as_absent: false,
+            ignore_keys: Vec::new(),
+            unordered_arrays: false,
+            strict_integers: false,
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Compares two values like `==`, but integers are compared by their
+    /// numeric value, taking the sign into account.
+    ///
+    /// `==` compares the raw `u64` representations of integers, so
+    /// a signed `-1` equals an unsigned [u64::MAX]. This method tells
+    /// them apart.
+    ///
+    /// ```rust
+    /// use plist_plus2::{Integer, Value};
+    ///
+    /// let minus_one: Value = Integer::new_signed(-1).into();
+    /// let max: Value = Integer::new_unsigned(u64::MAX).into();
+    /// assert!(minus_one == max);
+    /// assert!(!minus_one.eq_strict_integers(&max));
+    /// ```
+    pub fn eq_strict_integers(&self, other: &Value) -> bool {
+        let opts = EqOptions {
+            strict_integers: true,
+            ..Default::default()
+        };
+        self.eq_with(other, &opts)
+    }
+
+    /// Compares two values recursively using the given [EqOptions].
+    pub fn eq_with(&self, other: &Value, opts: &EqOptions) -> bool {
+        match (self, other) {
+            (Value::Dictionary(a), Value::Dictionary(b)) => {
+                let a = entries(a, opts);
+                let b = entries(b, opts);
+                a.len() == b.len()
+                    && a.iter().all(|(key, value)| {
+                        b.iter()
+                            .find(|(k, _)| k == key)
+                            .is_some_and(|(_, v)| value.eq_with(v, opts))
+                    })
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                if a.len() != b.len() {
+                    return false;
+                }
+                if !opts.unordered_arrays {
+                    return a.iter().zip(b.iter()).all(|(x, y)| x.eq_with(&y, opts));
+                }
+                // Every item must be matched with a distinct item of the other array
+                let b: Vec<Item> = b.iter().collect();
+                let mut used = vec![false; b.len()];
+                a.iter().all(|x| {
+                    let found = (0..b.len()).find(|&j| !used[j] && x.eq_with(&b[j], opts));
+                    found.map(|j| used[j] = true).is_some()
+                })
+            }
+            (Value::Integer(a), Value::Integer(b)) if opts.strict_integers => {
+                a.cmp_numeric(b).is_eq()
+            }
+            (Value::Real(a), Value::Real(b)) => {
+                floats_eq(a.as_float(), b.as_float(), opts.float_tolerance)
+            }
+            (Value::Integer(_), Value::Real(_)) | (Value::Real(_), Value::Integer(_))
+                if !opts.type_strict =>
+            {
+                match (as_f64(self), as_f64(other)) {
+                    (Some(a), Some(b)) => floats_eq(a, b, opts.float_tolerance),
+                    _ => false,
+                }
+            }
+            (Value::Key(a), Value::PString(b)) | (Value::PString(b), Value::Key(a))
+                if !opts.type_strict =>
+            {
+                a.get() == b.as_str()
+            }
+            _ => self == other,
+        }
+    }
+}
+
+/// Collects the dictionary entries that take part in the comparison.
+fn entries<'a>(dict: &'a Dictionary, opts: &EqOptions) -> Vec<(String, Item<'a>)> {
+    dict.iter()
+        .filter(|(key, value)| {
+            let ignored = opts.ignore_keys.contains(key);
+            let absent = opts.null_as_absent && value.is_null();
+            !ignored && !absent
+        })
+        .collect()
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Real(real) => Some(real.as_float()),
+        Value::Integer(integer) if integer.is_negative() => Some(integer.as_singed() as f64),
+        Value::Integer(integer) => Some(integer.as_unsinged() as f64),
+        _ => None,
+    }
+}
+
+fn floats_eq(a: f64, b: f64, tolerance: f64) -> bool {
+    a == b || (a - b).abs() <= tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn nested(list: Array, height: f64) -> Value<'static> {
+        dict!(
+            "name" => "pig",
+            "stats" => dict!("height" => height, "legs" => 4),
+            "list" => list
+        )
+        .into()
+    }
+
+    #[test]
+    fn eq_with_default() {
+        let opts = EqOptions::default();
+        let a = nested(array!(1, 2, 3), 1.5);
+        assert!(a.eq_with(&nested(array!(1, 2, 3), 1.5), &opts));
+        assert!(!a.eq_with(&nested(array!(3, 2, 1), 1.5), &opts));
+        assert!(!a.eq_with(&nested(array!(1, 2, 3), 1.50001), &opts));
+    }
+
+    #[test]
+    fn eq_with_tolerance_and_order() {
+        let opts = EqOptions {
+            float_tolerance: 0.001,
+            unordered_arrays: true,
+            ..Default::default()
+        };
+        let a = nested(array!(1, dict!("x" => 1), 1), 1.5);
+        assert!(a.eq_with(&nested(array!(dict!("x" => 1), 1, 1), 1.5001), &opts));
+        // Duplicates must match one to one
+        assert!(!a.eq_with(&nested(array!(dict!("x" => 1), 1, 2), 1.5), &opts));
+        assert!(!a.eq_with(&nested(array!(1, dict!("x" => 1), 1), 1.6), &opts));
+    }
+
+    #[test]
+    fn eq_with_loose_types() {
+        let a: Value = dict!("count" => 2, "ratio" => 0.5, "name" => "x").into();
+        let b: Value = dict!("count" => 2.0, "ratio" => 0.5, "name" => "x").into();
+        assert!(!a.eq_with(&b, &EqOptions::default()));
+        let opts = EqOptions {
+            type_strict: false,
+            ..Default::default()
+        };
+        assert!(a.eq_with(&b, &opts));
+        let c: Value = dict!("count" => -2.0, "ratio" => 0.5, "name" => "x").into();
+        assert!(!a.eq_with(&c, &opts));
+    }
+
+    #[test]
+    fn eq_with_ignored_and_null_entries() {
+        let a: Value = dict!(
+            "modified" => 1,
+            "inner" => dict!("modified" => 2, "value" => "x", "extra" => Null::new())
+        )
+        .into();
+        let b: Value = dict!(
+            "modified" => 3,
+            "inner" => dict!("value" => "x")
+        )
+        .into();
+        let opts = EqOptions {
+            ignore_keys: vec!["modified".to_string()],
+            ..Default::default()
+        };
+        assert!(!a.eq_with(&b, &opts));
+        let opts = EqOptions {
+            null_as_absent: true,
+            ..opts
+        };
+        assert!(a.eq_with(&b, &opts));
+        assert!(b.eq_with(&a, &opts));
+    }
+
+    #[test]
+    fn eq_strict_integers() {
+        let minus_one: Value = array!(dict!("id" => -1i64)).into();
+        let max: Value = array!(dict!("id" => u64::MAX)).into();
+        assert_eq!(minus_one, max);
+        assert!(!minus_one.eq_strict_integers(&max));
+        assert!(minus_one.eq_strict_integers(&minus_one.clone()));
+        assert!(Value::from(5u64).eq_strict_integers(&Value::from(5i64)));
+    }
+}
@@ -4,7 +4,12 @@ use crate::unsafe_bindings::{self, plist_err_t};
 pub(crate) const PLIST_ERROR_SUCCESS: plist_err_t = unsafe_bindings::plist_err_t_PLIST_ERR_SUCCESS;
 
 /// All possible errors that can occur when working with plist data.
+///
+/// More variants may be added in the future, so a match on an error needs
+/// a wildcard arm. A failure to parse the input is reported as either
+/// [Error::Parse] or [Error::ParseAt], check for both with [Error::is_parse].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
     /// One or more of the parameters are invalid.
     InvalidArg,
@@ -12,14 +17,34 @@ pub enum Error {
     Format,
     /// Parsing of the input format failed.
     Parse,
+    /// Parsing of the input format failed around the given position.
+    ///
+    /// `libplist` doesn't report where parsing stopped, so this is only
+    /// returned when the position can be found by other means, e.g. by
+    /// [from_xml](crate::from_xml) and [from_memory](crate::from_memory)
+    /// for a mismatched or unterminated XML tag.
+    ParseAt {
+        /// The byte offset of the input where the problem was found.
+        offset: usize,
+    },
     /// Not enough memory to handle the operation.
     NoMem,
     /// I/O error.
     IO,
+    /// The output exceeds the size limit of the operation.
+    TooLarge,
     /// Unknown error.
     Unknown,
 }
 
+impl Error {
+    /// Returns `true` if parsing of the input failed, whether the position
+    /// of the problem is known ([Error::ParseAt]) or not ([Error::Parse]).
+    pub fn is_parse(&self) -> bool {
+        matches!(self, Error::Parse | Error::ParseAt { .. })
+    }
+}
+
 impl From<plist_err_t> for Error {
     fn from(code: plist_err_t) -> Error {
         match code {
@@ -44,12 +69,16 @@ impl From<std::ffi::NulError> for Error {
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Error::ParseAt { offset } = self {
+            return write!(f, "Parsing of the input format failed at byte {offset}");
+        }
         f.write_str(match self {
             Error::InvalidArg => "One or more of the parameters are invalid",
             Error::Format => "The plist contains nodes not compatible with the output format",
-            Error::Parse => "Parsing of the input format failed",
+            Error::Parse | Error::ParseAt { .. } => "Parsing of the input format failed",
             Error::NoMem => "Not enough memory to handle the operation",
             Error::IO => "I/O error",
+            Error::TooLarge => "The output exceeds the size limit",
             Error::Unknown => "Unknown error",
         })
     }
@@ -0,0 +1,313 @@
+use crate::{Error, Value, unsafe_bindings};
+
+/// The serialization format of a plist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlistFormat {
+    /// An XML property list.
+    Xml,
+    /// A binary property list (`bplist00`).
+    Binary,
+    /// A JSON document.
+    Json,
+    /// An OpenStep ASCII property list.
+    OpenStep,
+}
+
+impl PlistFormat {
+    /// Converts a raw format code, such as the one returned by
+    /// [from_memory_raw_format](crate::from_memory_raw_format), into a [PlistFormat].
+    ///
+    /// Returns [None] for output-only formats, `PLIST_FORMAT_NONE`
+    /// and unknown codes.
+    pub fn from_raw_code(code: u32) -> Option<Self> {
+        Self::from_raw(code)
+    }
+
+    /// Converts a format reported by `libplist` into a [PlistFormat].
+    ///
+    /// Returns [None] for output-only formats and `PLIST_FORMAT_NONE`.
+    pub(crate) fn from_raw(format: unsafe_bindings::plist_format_t) -> Option<Self> {
+        match format {
+            unsafe_bindings::plist_format_t_PLIST_FORMAT_XML => Some(PlistFormat::Xml),
+            unsafe_bindings::plist_format_t_PLIST_FORMAT_BINARY => Some(PlistFormat::Binary),
+            unsafe_bindings::plist_format_t_PLIST_FORMAT_JSON => Some(PlistFormat::Json),
+            unsafe_bindings::plist_format_t_PLIST_FORMAT_OSTEP => Some(PlistFormat::OpenStep),
+            _ => None,
+        }
+    }
+}
+
+/// Guesses the plist format of a buffer by looking at its first bytes,
+/// without parsing it.
+///
+/// * `bplist00` marks a binary plist;
+/// * `<` (e.g. `<?xml`, `<!DOCTYPE` or `<plist`) marks an XML plist, unless
+///   it starts an OpenStep data literal like `<0fbd77>` or a GNUstep typed
+///   literal like `<*I5>`;
+/// * `[`, `{}`, or `{` followed by a quoted key and `:`, marks a JSON document;
+/// * anything else is assumed to be an OpenStep plist.
+///
+/// A UTF-8 byte order mark and leading whitespace are skipped. Returns [None]
+/// if the buffer is empty or contains whitespace only. Note that the detection
+/// is a heuristic: the buffer may still fail to parse.
+pub fn detect_format(bytes: &[u8]) -> Option<PlistFormat> {
+    if bytes.starts_with(b"bplist00") {
+        return Some(PlistFormat::Binary);
+    }
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut rest = bytes.iter().skip_while(|b| b.is_ascii_whitespace());
+    let format = match rest.next()? {
+        b'<' => match rest.next() {
+            Some(b) if b.is_ascii_hexdigit() || b.is_ascii_whitespace() || b"*>".contains(b) => {
+                PlistFormat::OpenStep
+            }
+            _ => PlistFormat::Xml,
+        },
+        b'[' => PlistFormat::Json,
+        b'{' => match rest.find(|b| !b.is_ascii_whitespace()) {
+            // OpenStep keys can be quoted too, the separator tells them apart
+            Some(b'"') => match after_quoted(&mut rest) {
+                Some(b'=') => PlistFormat::OpenStep,
+                _ => PlistFormat::Json,
+            },
+            Some(b'}') | None => PlistFormat::Json,
+            _ => PlistFormat::OpenStep,
+        },
+        _ => PlistFormat::OpenStep,
+    };
+    Some(format)
+}
+
+/// Skips the rest of a quoted string and returns the first
+/// non-whitespace byte after it.
+fn after_quoted<'a>(rest: &mut impl Iterator<Item = &'a u8>) -> Option<u8> {
+    loop {
+        match rest.next()? {
+            b'"' => break,
+            b'\\' => {
+                rest.next()?;
+            }
+            _ => {}
+        }
+    }
+    rest.find(|b| !b.is_ascii_whitespace()).copied()
+}
+
+/// Returns the possible lengths of a binary plist at the start of the buffer,
+/// shortest first.
+///
+/// A binary plist ends with a 32 bytes trailer that points back at its offset
+/// table, so every position where such a trailer would be consistent
+/// with the data before it is a candidate.
+pub(crate) fn binary_lengths(bytes: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    const TRAILER: usize = 32;
+    (8 + TRAILER..=bytes.len()).filter(move |&end| {
+        let trailer = &bytes[end - TRAILER..end];
+        let number = |at: usize| u64::from_be_bytes(trailer[at..at + 8].try_into().unwrap());
+        let (offset_size, ref_size) = (trailer[6] as u64, trailer[7] as u64);
+        let (objects, top, table) = (number(8), number(16), number(24));
+        (1..=8).contains(&offset_size)
+            && (1..=8).contains(&ref_size)
+            && top < objects
+            && table >= 8
+            && objects
+                .checked_mul(offset_size)
+                .and_then(|size| size.checked_add(table))
+                .is_some_and(|size| size == (end - TRAILER) as u64)
+    })
+}
+
+/// Returns the length of a text plist at the start of the buffer together
+/// with the whitespace following it, or [None] if its end can't be found.
+///
+/// XML plists end with `</plist>`. JSON and OpenStep plists must have
+/// an array or a dictionary as the root, which ends with the bracket
+/// matching the first one.
+pub(crate) fn text_length(bytes: &[u8], format: PlistFormat) -> Option<usize> {
+    let end = match format {
+        PlistFormat::Xml => {
+            let closing = b"</plist>";
+            bytes
+                .windows(closing.len())
+                .position(|window| window == closing)?
+                + closing.len()
+        }
+        PlistFormat::Json | PlistFormat::OpenStep => bracketed_length(bytes)?,
+        PlistFormat::Binary => return None,
+    };
+    let whitespace = bytes[end..]
+        .iter()
+        .take_while(|b| b.is_ascii_whitespace())
+        .count();
+    Some(end + whitespace)
+}
+
+/// Returns the position right after the bracket that closes the first one,
+/// skipping quoted strings and comments.
+fn bracketed_length(bytes: &[u8]) -> Option<usize> {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace())?;
+    if !b"[{(".contains(&bytes[start]) {
+        return None;
+    }
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (quote @ (b'"' | b'\''), _) => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            (b'/', Some(b'/')) => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            (b'/', Some(b'*')) => {
+                i += 2;
+                while i + 1 < bytes.len() && &bytes[i..i + 2] != b"*/" {
+                    i += 1;
+                }
+                i += 1;
+            }
+            (b'[' | b'{' | b'(', _) => depth += 1,
+            (b']' | b'}' | b')', _) => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+impl Value<'_> {
+    /// Serializes the value in the given format, e.g. the one returned
+    /// by [detect_format] for the original input.
+    ///
+    /// Text formats are prettified, the same way `libplist` tools write them.
+    pub fn to_format(&self, format: PlistFormat) -> Result<Vec<u8>, Error> {
+        export(self, format)
+    }
+
+    /// Serializes the value like [Value::to_format], but returns
+    /// [Error::TooLarge] if the output is longer than `max_bytes`.
+    ///
+    /// libplist always serializes the whole tree, so the limit is checked
+    /// once the output is ready. It protects the consumers of the output
+    /// rather than the serialization itself.
+    pub fn to_bytes_limited(
+        &self,
+        format: PlistFormat,
+        max_bytes: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let output = export(self, format)?;
+        if output.len() > max_bytes {
+            return Err(Error::TooLarge);
+        }
+        Ok(output)
+    }
+}
+
+/// Serializes a value in the given format.
+///
+/// Text formats are prettified, the same way `libplist` tools write them.
+pub(crate) fn export(value: &Value, format: PlistFormat) -> Result<Vec<u8>, Error> {
+    Ok(match format {
+        PlistFormat::Xml => value.to_xml()?.into_bytes(),
+        PlistFormat::Binary => value.to_bytes()?,
+        PlistFormat::Json => value.to_json(true)?.into_bytes(),
+        PlistFormat::OpenStep => value.to_openstep(true)?.into_bytes(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_file(path: &str) -> Option<PlistFormat> {
+        let bytes = std::fs::read(path).unwrap();
+        detect_format(&bytes[..bytes.len().min(32)])
+    }
+
+    #[test]
+    fn detect_sample_formats() {
+        assert_eq!(
+            detect_file("./tests/ascii-animals.plist"),
+            Some(PlistFormat::OpenStep)
+        );
+        assert_eq!(
+            detect_file("./tests/ascii-sample.plist"),
+            Some(PlistFormat::OpenStep)
+        );
+        assert_eq!(
+            detect_file("./tests/binary.plist"),
+            Some(PlistFormat::Binary)
+        );
+        assert_eq!(
+            detect_file("./tests/binary_NSKeyedArchiver.plist"),
+            Some(PlistFormat::Binary)
+        );
+        assert_eq!(
+            detect_file("./tests/utf16_bplist.plist"),
+            Some(PlistFormat::Binary)
+        );
+        assert_eq!(detect_file("./tests/book.plist"), Some(PlistFormat::Xml));
+        assert_eq!(
+            detect_file("./tests/xml-animals.plist"),
+            Some(PlistFormat::Xml)
+        );
+        assert_eq!(detect_file("./tests/xml.plist"), Some(PlistFormat::Xml));
+    }
+
+    #[test]
+    fn detect_other_formats() {
+        assert_eq!(detect_format(b"  {\"a\": 1}"), Some(PlistFormat::Json));
+        assert_eq!(
+            detect_format(b"{\"a \\\" = b\" : 1}"),
+            Some(PlistFormat::Json)
+        );
+        assert_eq!(
+            detect_format(b"{ \"my key\" = 1; }"),
+            Some(PlistFormat::OpenStep)
+        );
+        assert_eq!(detect_format(b"[1, 2]"), Some(PlistFormat::Json));
+        assert_eq!(detect_format(b"(1, 2)"), Some(PlistFormat::OpenStep));
+        assert_eq!(detect_format(b"<0fbd77>"), Some(PlistFormat::OpenStep));
+        assert_eq!(
+            detect_format(b"<plist version=\"1.0\">"),
+            Some(PlistFormat::Xml)
+        );
+        assert_eq!(detect_format(b"<*I5>"), Some(PlistFormat::OpenStep));
+        assert_eq!(detect_format(b" \n\t"), None);
+        assert_eq!(detect_format(b""), None);
+    }
+
+    #[test]
+    fn to_bytes_limited() {
+        let plist: Value = (0..1000).collect::<crate::Array>().into();
+        for format in [PlistFormat::Xml, PlistFormat::Binary, PlistFormat::Json] {
+            let size = plist.to_format(format).unwrap().len();
+            assert_eq!(plist.to_bytes_limited(format, size).unwrap().len(), size);
+            assert_eq!(plist.to_bytes_limited(format, 100), Err(Error::TooLarge));
+        }
+    }
+
+    #[test]
+    fn reserialize_in_detected_format() {
+        for path in ["./tests/xml-animals.plist", "./tests/ascii-animals.plist"] {
+            let bytes = std::fs::read(path).unwrap();
+            let format = detect_format(&bytes).unwrap();
+            let mut plist = crate::from_memory(&bytes).unwrap();
+            plist.as_dictionary_mut().unwrap().insert("Edited", "yes");
+
+            let output = plist.to_format(format).unwrap();
+            assert_eq!(detect_format(&output), Some(format));
+            assert_eq!(crate::from_memory(&output).unwrap(), plist);
+        }
+    }
+}
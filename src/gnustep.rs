@@ -0,0 +1,267 @@
+//! Support for the typed literals of the GNUstep OpenStep dialect:
+//! `<*I123>`, `<*R1.5>`, `<*BY>` and `<*D2001-01-01 00:00:00 +0000>`.
+//!
+//! libplist doesn't know about them, so before parsing every literal is
+//! replaced with a unique placeholder string. Once the tree is built, the
+//! placeholders are swapped for the nodes of the right types.
+
+use crate::types::date;
+use crate::{Boolean, Date, Error, Integer, Real, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Text with the typed literals replaced by placeholders and the nodes
+/// they stand for.
+pub(crate) struct Preprocessed {
+    pub(crate) text: String,
+    nodes: HashMap<String, Value<'static>>,
+}
+
+/// Replaces every typed literal outside of quoted strings and comments
+/// with a placeholder. Returns [Error::Parse] if a literal is malformed.
+pub(crate) fn preprocess(text: &str) -> Result<Preprocessed, Error> {
+    let mut nodes = HashMap::new();
+    if !text.contains("<*") {
+        return Ok(Preprocessed {
+            text: text.to_string(),
+            nodes,
+        });
+    }
+    let mut prefix = String::from("plist_plus2_gnustep_");
+    while text.contains(prefix.as_str()) {
+        prefix.push('_');
+    }
+
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (quote @ (b'"' | b'\''), _) => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            (b'/', Some(b'/')) => {
+                i = find(bytes, i, b"\n").map_or(bytes.len(), |end| end + 1);
+            }
+            (b'/', Some(b'*')) => {
+                i = find(bytes, i + 2, b"*/").map_or(bytes.len(), |end| end + 2);
+            }
+            (b'<', Some(b'*')) => {
+                let end = find(bytes, i, b">").ok_or(Error::Parse)?;
+                let placeholder = format!("{prefix}{}", nodes.len());
+                nodes.insert(placeholder.clone(), parse_literal(&text[i + 2..end])?);
+                out.push_str(&text[copied..i]);
+                out.push_str(&placeholder);
+                i = end + 1;
+                copied = i;
+            }
+            _ => i += 1,
+        }
+    }
+    out.push_str(&text[copied.min(text.len())..]);
+    Ok(Preprocessed { text: out, nodes })
+}
+
+impl Preprocessed {
+    /// Puts the typed nodes in place of their placeholders.
+    ///
+    /// Returns [Error::Parse] if a literal was used as a dictionary key,
+    /// since keys can only be strings.
+    pub(crate) fn restore<'a>(mut self, mut value: Value<'a>) -> Result<Value<'a>, Error> {
+        if self.nodes.is_empty() {
+            return Ok(value);
+        }
+        if let Some(node) = self.take(&value) {
+            return Ok(node);
+        }
+        self.restore_children(&mut value);
+        // Every placeholder left behind is a dictionary key
+        if !self.nodes.is_empty() {
+            return Err(Error::Parse);
+        }
+        Ok(value)
+    }
+
+    fn take(&mut self, value: &Value) -> Option<Value<'static>> {
+        let string = value.as_string()?;
+        let placeholder = std::str::from_utf8(string.as_bytes()).ok()?;
+        self.nodes.remove(placeholder)
+    }
+
+    fn restore_children(&mut self, value: &mut Value) {
+        match value {
+            Value::Array(array) => {
+                for index in 0..array.len() {
+                    let node = self.take(&array.get(index).unwrap());
+                    match node {
+                        Some(node) => array.set(node, index),
+                        None => self.restore_children(&mut array.get_mut(index).unwrap()),
+                    }
+                }
+            }
+            Value::Dictionary(dict) => {
+                let keys: Vec<String> = dict.iter().map(|(key, _)| key).collect();
+                for key in keys {
+                    let node = self.take(&dict.get(key.as_str()).unwrap());
+                    match node {
+                        Some(node) => dict.insert(key, node),
+                        None => self.restore_children(&mut dict.get_mut(key).unwrap()),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+/// Builds a node from the contents of a literal without the `<*` and `>`.
+fn parse_literal(literal: &str) -> Result<Value<'static>, Error> {
+    let (kind, body) = literal.split_at_checked(1).ok_or(Error::Parse)?;
+    let body = body.trim();
+    let value = match kind {
+        "I" => match body.parse::<i64>() {
+            Ok(value) => Integer::new_signed(value).into(),
+            Err(_) => Integer::new_unsigned(body.parse().map_err(|_| Error::Parse)?).into(),
+        },
+        "R" => Real::new(body.parse().map_err(|_| Error::Parse)?).into(),
+        "B" => match body {
+            "Y" => Boolean::new(true).into(),
+            "N" => Boolean::new(false).into(),
+            _ => return Err(Error::Parse),
+        },
+        "D" => Date::new(parse_date(body).ok_or(Error::Parse)?).into(),
+        _ => return Err(Error::Parse),
+    };
+    Ok(value)
+}
+
+/// Parses a `YYYY-MM-DD HH:MM:SS +HHMM` date into a time since the Unix Epoch.
+fn parse_date(date: &str) -> Option<Duration> {
+    let mut parts = date.split_whitespace();
+    let (day, time, zone) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let numbers = |text: &str, separator| -> Option<Vec<i64>> {
+        text.split(separator)
+            .map(|part| part.parse().ok())
+            .collect()
+    };
+    let [year, month, day] = numbers(day, '-')?[..] else {
+        return None;
+    };
+    let [hour, minute, second] = numbers(time, ':')?[..] else {
+        return None;
+    };
+    let (sign, zone) = match zone.split_at_checked(1)? {
+        ("+", zone) => (1, zone),
+        ("-", zone) => (-1, zone),
+        _ => return None,
+    };
+    let zone: i64 = zone.parse().ok().filter(|_| zone.len() == 4)?;
+    let valid = (1..=12).contains(&month)
+        && (1..=31).contains(&day)
+        && (0..24).contains(&hour)
+        && (0..60).contains(&minute)
+        && (0..=60).contains(&second);
+    if !valid {
+        return None;
+    }
+
+    let days = date::days_from_civil(year, month, day);
+    let offset = sign * ((zone / 100) * 3600 + (zone % 100) * 60);
+    let secs = days * 86400 + hour * 3600 + minute * 60 + second - offset;
+    u64::try_from(secs).ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_date;
+    use crate::{ValueType, from_openstep};
+    use std::time::Duration;
+
+    #[test]
+    fn typed_integer() {
+        let plist = from_openstep("(<*I123>, <*I-5>, <*I18446744073709551615>)").unwrap();
+        let array = plist.as_array().unwrap();
+        assert_eq!(array.get(0).unwrap().as_integer().unwrap().as_singed(), 123);
+        assert_eq!(array.get(1).unwrap().as_integer().unwrap().as_singed(), -5);
+        let max = array.get(2).unwrap();
+        assert_eq!(max.as_integer().unwrap().as_unsinged(), u64::MAX);
+    }
+
+    #[test]
+    fn typed_real() {
+        let plist = from_openstep("{ Ratio = <*R1.5>; }").unwrap();
+        let ratio = plist.as_dictionary().unwrap().get("Ratio").unwrap();
+        assert_eq!(ratio.as_real().unwrap().as_float(), 1.5);
+    }
+
+    #[test]
+    fn typed_boolean() {
+        let plist = from_openstep("{ Yes = <*BY>; No = <*B N>; }").unwrap();
+        let dict = plist.as_dictionary().unwrap();
+        assert!(dict.get("Yes").unwrap().as_boolean().unwrap().as_bool());
+        assert!(!dict.get("No").unwrap().as_boolean().unwrap().as_bool());
+    }
+
+    #[test]
+    fn typed_date() {
+        let plist = from_openstep("<*D2019-01-04 21:00:00 +0000>").unwrap();
+        assert_eq!(plist.value_type(), ValueType::Date);
+        let date = plist.as_date().unwrap().get();
+        assert_eq!(date, Duration::from_secs(1546635600));
+
+        let shifted = parse_date("2019-01-05 00:00:00 +0300").unwrap();
+        assert_eq!(shifted, Duration::from_secs(1546635600));
+        assert_eq!(parse_date("2019-13-01 00:00:00 +0000"), None);
+    }
+
+    #[test]
+    fn typed_nested_and_quoted() {
+        let text = r#"{
+            // <*I1> in a comment stays a comment
+            List = ( <*I1>, "<*I2>", { Inner = <*BY>; } );
+        }"#;
+        let plist = from_openstep(text).unwrap();
+        let list = plist.as_dictionary().unwrap().get("List").unwrap();
+        let list = list.as_array().unwrap();
+        assert_eq!(list.get(0).unwrap().value_type(), ValueType::Integer);
+        assert_eq!(list.get(1).unwrap().as_string().unwrap().as_str(), "<*I2>");
+        let inner = list.get(2).unwrap();
+        let inner = inner.as_dictionary().unwrap().get("Inner").unwrap();
+        assert_eq!(inner.value_type(), ValueType::Boolean);
+    }
+
+    #[test]
+    fn typed_malformed() {
+        assert!(from_openstep("(<*Iabc>)").is_err());
+        assert!(from_openstep("(<*X1>)").is_err());
+        assert!(from_openstep("(<*I1)").is_err());
+    }
+
+    #[test]
+    fn typed_key() {
+        assert_eq!(
+            from_openstep("{ <*I5> = x; }").unwrap_err(),
+            crate::Error::Parse
+        );
+        assert_eq!(
+            from_openstep("{ a = { <*BY> = <*I1>; }; }").unwrap_err(),
+            crate::Error::Parse
+        );
+    }
+}
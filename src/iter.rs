@@ -0,0 +1,83 @@
+use crate::{Item, Value, ValueType, unsafe_bindings};
+use std::marker::PhantomData;
+
+/// Returns the pointers to the children of an array or the values of
+/// a dictionary, in order. Any other node has no children.
+pub(crate) fn children(pointer: unsafe_bindings::plist_t) -> Vec<unsafe_bindings::plist_t> {
+    let typ: ValueType = unsafe { unsafe_bindings::plist_get_node_type(pointer) }.into();
+    let mut children = Vec::new();
+    match typ {
+        ValueType::Array => {
+            let len = unsafe { unsafe_bindings::plist_array_get_size(pointer) };
+            for i in 0..len {
+                children.push(unsafe { unsafe_bindings::plist_array_get_item(pointer, i) });
+            }
+        }
+        ValueType::Dictionary => {
+            let mut iter = std::ptr::null_mut();
+            unsafe { unsafe_bindings::plist_dict_new_iter(pointer, &mut iter) };
+            loop {
+                let mut item = std::ptr::null_mut();
+                unsafe {
+                    unsafe_bindings::plist_dict_next_item(
+                        pointer,
+                        iter,
+                        std::ptr::null_mut(),
+                        &mut item,
+                    )
+                };
+                if item.is_null() {
+                    break;
+                }
+                children.push(item);
+            }
+            unsafe { libc::free(iter) };
+        }
+        _ => {}
+    }
+    children
+}
+
+/// Wraps a pointer to a node of a borrowed tree into an [Item].
+///
+/// # Safety
+/// The pointer must belong to a tree that outlives `'a`.
+pub(crate) unsafe fn borrowed_item<'a>(pointer: unsafe_bindings::plist_t) -> Item<'a> {
+    let mut value = unsafe { crate::from_pointer(pointer) };
+    value.as_node_mut().set_false_drop(true);
+    Item(value)
+}
+
+/// A depth-first iterator over the scalar leaves of a tree.
+///
+/// Created by [Value::scalars].
+pub(crate) struct Scalars<'a> {
+    stack: Vec<unsafe_bindings::plist_t>,
+    phantom: PhantomData<&'a Value<'a>>,
+}
+
+impl<'a> Scalars<'a> {
+    pub(crate) fn new(root: &'a Value) -> Self {
+        Self {
+            stack: vec![root.pointer()],
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for Scalars<'a> {
+    type Item = Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(pointer) = self.stack.pop() {
+            let typ: ValueType = unsafe { unsafe_bindings::plist_get_node_type(pointer) }.into();
+            match typ {
+                ValueType::Array | ValueType::Dictionary => {
+                    self.stack.extend(children(pointer).into_iter().rev());
+                }
+                _ => return Some(unsafe { borrowed_item(pointer) }),
+            }
+        }
+        None
+    }
+}
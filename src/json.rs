@@ -0,0 +1,186 @@
+use crate::types::data::encode_base64;
+use crate::{Array, Dictionary, Error, Integer, Value};
+
+/// How [Value::to_json_with_options] encodes data nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum JsonData {
+    /// A base64 string with padding, e.g. `"AQID"`.
+    #[default]
+    Base64,
+    /// An array of byte values, e.g. `[1, 2, 3]`.
+    IntArray,
+}
+
+/// How [Value::to_json_with_options] encodes date nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum JsonDate {
+    /// An RFC 3339 string in UTC, see [Date::to_rfc3339](crate::Date::to_rfc3339).
+    #[default]
+    Rfc3339,
+    /// A number of seconds since the Unix Epoch, with microseconds
+    /// as the fractional part.
+    UnixSeconds,
+}
+
+/// How [Value::to_json_with_options] encodes uid nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum JsonUid {
+    /// An object of a single `CF$UID` key, the way XML plists store uids.
+    #[default]
+    Object,
+    /// A plain integer.
+    Integer,
+}
+
+/// Options of [Value::to_json_with_options].
+///
+/// `libplist` can't export data, date and uid nodes to JSON at all,
+/// so these options choose how they are encoded. Null nodes always
+/// become `null`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct JsonOptions {
+    /// The encoding of data nodes.
+    pub data: JsonData,
+    /// The encoding of date nodes.
+    pub date: JsonDate,
+    /// The encoding of uid nodes.
+    pub uid: JsonUid,
+    /// Compose a prettified JSON string.
+    pub prettify: bool,
+}
+
+impl Value<'_> {
+    /// Exports the plist node as a JSON format, encoding the types JSON
+    /// doesn't have as the options say.
+    ///
+    /// The tree is copied with the data, date and uid nodes replaced
+    /// by their encodings, and the copy is exported with [Value::to_json].
+    ///
+    /// # Example
+    /// ```rust
+    /// use plist_plus2::{JsonData, JsonOptions, Value, dict};
+    ///
+    /// let plist: Value = dict!("bytes" => vec![1u8, 2, 3]).into();
+    /// let options = JsonOptions { data: JsonData::IntArray, ..Default::default() };
+    /// assert_eq!(plist.to_json_with_options(options).unwrap(), r#"{"bytes":[1,2,3]}"#);
+    /// ```
+    pub fn to_json_with_options(&self, options: JsonOptions) -> Result<String, Error> {
+        encode(self, &options).to_json(options.prettify)
+    }
+
+    /// Exports the plist node as a JSON format without any insignificant
+    /// whitespace.
+    ///
+    /// The output of [Value::to_json] is stripped of the whitespace outside
+    /// of string literals, so the result is minified regardless of how
+    /// `libplist` formats it.
+    pub fn to_json_compact(&self) -> Result<String, Error> {
+        Ok(minify(&self.to_json(false)?))
+    }
+}
+
+/// Removes the whitespace outside of the string literals of a JSON text.
+fn minify(json: &str) -> String {
+    let mut minified = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c.is_ascii_whitespace() {
+            continue;
+        }
+        minified.push(c);
+    }
+    minified
+}
+
+/// Returns a copy of the tree that `libplist` can export to JSON.
+fn encode<'b>(value: &Value, options: &JsonOptions) -> Value<'b> {
+    match value {
+        Value::Array(array) => array
+            .iter()
+            .map(|item| encode(&item, options))
+            .collect::<Array>()
+            .into(),
+        Value::Dictionary(dict) => dict
+            .iter()
+            .map(|(key, item)| (key, encode(&item, options)))
+            .collect::<Dictionary>()
+            .into(),
+        Value::Data(data) => match options.data {
+            JsonData::Base64 => encode_base64(data.as_bytes()).into(),
+            JsonData::IntArray => data.as_bytes().iter().copied().collect::<Array>().into(),
+        },
+        Value::Date(date) => match options.date {
+            JsonDate::Rfc3339 => date.to_rfc3339().into(),
+            JsonDate::UnixSeconds => (date.unix_micros() as f64 / 1e6).into(),
+        },
+        Value::Uid(uid) => match options.uid {
+            JsonUid::Object => crate::dict!("CF$UID" => uid.get()).into(),
+            JsonUid::Integer => Integer::from(uid.get()).into(),
+        },
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::time::Duration;
+
+    #[test]
+    fn json_with_options() {
+        let plist: Value = dict!(
+            "data" => vec![1u8, 2, 3, 4],
+            "date" => Duration::from_micros(1546635600500000),
+            "uid" => Uid::new(7),
+            "list" => array!(Null::new(), vec![255u8])
+        )
+        .into();
+        assert!(plist.to_json(false).is_err());
+
+        let json = plist.to_json_with_options(JsonOptions::default()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":"AQIDBA==","date":"2019-01-04T21:00:00.500000Z","uid":{"CF$UID":7},"list":[null,"/w=="]}"#
+        );
+
+        let options = JsonOptions {
+            data: JsonData::IntArray,
+            date: JsonDate::UnixSeconds,
+            uid: JsonUid::Integer,
+            prettify: false,
+        };
+        let json = plist.to_json_with_options(options).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":[1,2,3,4],"date":1546635600.5,"uid":7,"list":[null,[255]]}"#
+        );
+    }
+
+    #[test]
+    fn json_compact() {
+        let plist: Value = dict!(
+            "name" => "pink pig",
+            "quote" => "say \"hi there\"",
+            "list" => array!(1, dict!("a b" => true))
+        )
+        .into();
+        let json = plist.to_json_compact().unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"pink pig","quote":"say \"hi there\"","list":[1,{"a b":true}]}"#
+        );
+        assert_eq!(super::minify("{ \"a\" :\n [1, 2] }"), r#"{"a":[1,2]}"#);
+        assert_eq!(from_json(json).unwrap(), plist);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{Value, ValueType, from_pointer, iter, unsafe_bindings};
+use std::marker::PhantomData;
+
+/// A cheap handle to a node inside a plist tree that's copied only on demand.
+///
+/// Creating a `LazyValue` with [Value::lazy_child] doesn't copy anything,
+/// it only remembers where the node is. The handle borrows the tree it was
+/// created from, so the tree can't be modified or dropped while the handle
+/// is alive. Call [LazyValue::materialize] to get an owned deep copy that
+/// outlives the tree.
+#[derive(Clone, Copy)]
+pub struct LazyValue<'a> {
+    pointer: unsafe_bindings::plist_t,
+    phantom: PhantomData<&'a Value<'a>>,
+}
+
+impl<'a> LazyValue<'a> {
+    pub(crate) fn new(pointer: unsafe_bindings::plist_t) -> Self {
+        Self {
+            pointer,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the type of the node without copying it.
+    pub fn value_type(&self) -> ValueType {
+        unsafe { unsafe_bindings::plist_get_node_type(self.pointer) }.into()
+    }
+
+    /// Returns a reference to the node without copying it.
+    pub fn get(&self) -> crate::Item<'a> {
+        unsafe { iter::borrowed_item(self.pointer) }
+    }
+
+    /// Deep-copies the node and its children.
+    ///
+    /// Every call makes a new copy that is independent from the tree
+    /// and from the other copies.
+    pub fn materialize<'b>(&self) -> Value<'b> {
+        unsafe { from_pointer(unsafe_bindings::plist_copy(self.pointer)) }
+    }
+}
+
+impl std::fmt::Debug for LazyValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyValue")
+            .field("value_type", &self.value_type())
+            .finish()
+    }
+}
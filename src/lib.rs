@@ -1,10 +1,46 @@
 #![doc = include_str!("../README.md")]
 
+mod archive;
+mod coercion;
+mod document;
+mod eq;
 mod error;
+mod format;
+mod gnustep;
+mod iter;
+mod json;
+mod lazy;
+mod metrics;
+mod path;
+mod repair;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod snapshot;
+mod stream;
+#[cfg(feature = "toml")]
+mod toml_support;
+mod tree;
 mod types;
 mod unsafe_bindings;
+#[cfg(feature = "yaml")]
+mod yaml_support;
+pub use archive::*;
+pub use coercion::*;
+pub use document::*;
+pub use eq::*;
 pub use error::*;
+pub use format::*;
+pub use json::*;
+pub use lazy::*;
+pub use metrics::*;
+pub use path::*;
+pub use repair::*;
+pub use snapshot::*;
+pub use stream::*;
+pub use tree::*;
 pub use types::*;
+#[cfg(feature = "yaml")]
+pub use yaml_support::from_yaml;
 
 use std::ffi::CString;
 
@@ -24,12 +60,36 @@ pub enum Value<'a> {
     Uid(Uid<'a>),
 }
 
+/// The key [Value::into_preferences_dict] wraps non-dictionary values under.
+pub const PREFERENCES_WRAP_KEY: &str = "Value";
+
 impl<'a> Value<'a> {
     /// Exports the plist node as an XML format.
+    ///
+    /// Some nodes, like infinite or NaN reals, have no proper XML
+    /// representation and may produce output other parsers reject.
+    /// Use [Value::xml_compatibility] to find them beforehand.
     pub fn to_xml(&self) -> Result<String, Error> {
         self.as_node().to_xml()
     }
 
+    /// Exports the plist node as an XML fragment, i.e. only the markup of the
+    /// node itself (e.g. `<dict>...</dict>`) without the XML declaration,
+    /// DOCTYPE and the `<plist>` root element.
+    ///
+    /// This is useful for embedding a plist into a larger document.
+    pub fn to_xml_fragment(&self) -> Result<String, Error> {
+        let xml = self.to_xml()?;
+        let start = xml
+            .find("<plist")
+            .and_then(|plist| xml[plist..].find('>').map(|end| plist + end + 1))
+            .ok_or(Error::Unknown)?;
+        let end = xml.rfind("</plist>").ok_or(Error::Unknown)?;
+        Ok(xml[start..end]
+            .trim_matches(|c| c == '\n' || c == '\r')
+            .to_string())
+    }
+
     /// Exports the plist node as a JSON format.
     ///
     /// Set `prettify` to `true` to compose a prettified JSON string.
@@ -37,6 +97,24 @@ impl<'a> Value<'a> {
         self.as_node().to_json(prettify)
     }
 
+    /// Exports the plist node as a JSON format with the keys of every
+    /// dictionary sorted lexicographically.
+    ///
+    /// Unlike [Value::to_json], the output doesn't depend on the insertion
+    /// order, which makes it suitable for diffing and caching.
+    /// The value is copied, so the original order is left untouched.
+    pub fn to_json_sorted(&self, prettify: bool) -> Result<String, Error> {
+        let mut sorted = self.clone();
+        sorted.sort_keys();
+        sorted.to_json(prettify)
+    }
+
+    /// Sorts the key/value pairs of every dictionary in the tree
+    /// lexicographically by key.
+    pub fn sort_keys(&mut self) {
+        unsafe { unsafe_bindings::plist_sort(self.pointer()) }
+    }
+
     /// Exports the plist node as a binary encoded plist.
     pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
         self.as_node().to_bytes()
@@ -49,11 +127,59 @@ impl<'a> Value<'a> {
         self.as_node().to_openstep(prettify)
     }
 
+    /// Exports the plist node as an XML format directly into a writer.
+    ///
+    /// See [Node::write_xml] for details.
+    pub fn write_xml<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+        types::write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_xml(self.pointer(), out, len)
+        })
+    }
+
+    /// Exports the plist node as a JSON format directly into a writer.
+    ///
+    /// Set `prettify` to `true` to compose a prettified JSON string.
+    pub fn write_json<W: std::io::Write>(&self, w: &mut W, prettify: bool) -> Result<(), Error> {
+        types::write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_json(self.pointer(), out, len, prettify as _)
+        })
+    }
+
+    /// Exports the plist node as a binary encoded plist directly into a writer.
+    pub fn write_bytes<W: std::io::Write>(&self, w: &mut W) -> Result<(), Error> {
+        types::write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_bin(self.pointer(), out, len)
+        })
+    }
+
+    /// Exports the plist node to an OpenStep ASCII encoded plist
+    /// directly into a writer.
+    ///
+    /// Set `prettify` to `true` to compose a prettified string.
+    pub fn write_openstep<W: std::io::Write>(
+        &self,
+        w: &mut W,
+        prettify: bool,
+    ) -> Result<(), Error> {
+        types::write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_openstep(self.pointer(), out, len, prettify as _)
+        })
+    }
+
     /// Returns the pointer to a corresponding C structure.
     pub fn pointer(&self) -> unsafe_bindings::plist_t {
         self.as_node().pointer()
     }
 
+    /// Returns `true` if both values wrap the same node.
+    ///
+    /// Unlike `==`, which compares the trees structurally, this compares
+    /// the underlying pointers. Two owned values should never wrap the same
+    /// node, otherwise it would be freed twice.
+    pub fn ptr_eq(&self, other: &Value) -> bool {
+        self.pointer() == other.pointer()
+    }
+
     /// Returns a mutable reference to the value as a dynamic [Node] object.
     pub(crate) fn as_node_mut(&mut self) -> &mut dyn Node {
         match self {
@@ -88,6 +214,60 @@ impl<'a> Value<'a> {
         }
     }
 
+    /// Consumes the value and returns the underlying node as a boxed [Node]
+    /// trait object.
+    ///
+    /// This allows writing code that is generic over node types, e.g. for
+    /// exporting. The node is freed when the box is dropped, just as
+    /// the value would be.
+    pub fn into_boxed_node(self) -> Box<dyn Node + 'a> {
+        match self {
+            Value::Array(v) => Box::new(v),
+            Value::Boolean(v) => Box::new(v),
+            Value::Data(v) => Box::new(v),
+            Value::Date(v) => Box::new(v),
+            Value::Dictionary(v) => Box::new(v),
+            Value::Integer(v) => Box::new(v),
+            Value::Key(v) => Box::new(v),
+            Value::Real(v) => Box::new(v),
+            Value::PString(v) => Box::new(v),
+            Value::Uid(v) => Box::new(v),
+            Value::Null(v) => Box::new(v),
+        }
+    }
+
+    /// Consumes the value and returns a dictionary suitable for
+    /// a CFPreferences-style plist, whose root must be a dictionary.
+    ///
+    /// A dictionary is returned as is, any other value is wrapped into
+    /// a dictionary under the [PREFERENCES_WRAP_KEY] key. Use
+    /// [Value::into_preferences_dict_with] to pick another key or to reject
+    /// non-dictionary roots.
+    pub fn into_preferences_dict(self) -> Result<Dictionary<'a>, Error> {
+        self.into_preferences_dict_with(Some(PREFERENCES_WRAP_KEY))
+    }
+
+    /// Like [Value::into_preferences_dict], but wraps a non-dictionary value
+    /// under the given key, or returns [Error::Format] for it if `wrap_key`
+    /// is [None].
+    ///
+    /// Returns [Error::InvalidArg] if the key contains an internal 0 byte.
+    pub fn into_preferences_dict_with(
+        self,
+        wrap_key: Option<&str>,
+    ) -> Result<Dictionary<'a>, Error> {
+        match (self, wrap_key) {
+            (Value::Dictionary(dict), _) => Ok(dict),
+            (_, None) => Err(Error::Format),
+            (_, Some(key)) if key.contains('\0') => Err(Error::InvalidArg),
+            (value, Some(key)) => {
+                let mut dict = Dictionary::new();
+                dict.insert(key, value);
+                Ok(dict)
+            }
+        }
+    }
+
     /// If the [Value] is an Array, returns an immutable reference to the associated [Array].
     ///
     /// Returns [None] otherwise.
@@ -138,6 +318,14 @@ impl<'a> Value<'a> {
         }
     }
 
+    /// If the [Value] is a Data, returns its bytes.
+    ///
+    /// Returns [Error::InvalidArg] otherwise, which makes it handy
+    /// with `?` next to the `TryFrom<&Value>` conversions into primitives.
+    pub fn try_as_bytes(&self) -> Result<&[u8], Error> {
+        self.as_data().map(Data::as_bytes).ok_or(Error::InvalidArg)
+    }
+
     /// If the [Value] is a Data, returns a mutable reference to the associated [Data].
     ///
     /// Returns [None] otherwise.
@@ -388,11 +576,391 @@ impl<'a> Value<'a> {
         }
     }
 
+    /// Returns the type of the value.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Value::Array(_) => ValueType::Array,
+            Value::Boolean(_) => ValueType::Boolean,
+            Value::Data(_) => ValueType::Data,
+            Value::Date(_) => ValueType::Date,
+            Value::Dictionary(_) => ValueType::Dictionary,
+            Value::Integer(_) => ValueType::Integer,
+            Value::Key(_) => ValueType::Key,
+            Value::Null(_) => ValueType::Null,
+            Value::Real(_) => ValueType::Real,
+            Value::PString(_) => ValueType::String,
+            Value::Uid(_) => ValueType::Uid,
+        }
+    }
+
+    /// Tries to convert a scalar value into a value of the `target` type.
+    ///
+    /// Supported conversions:
+    /// * a string is parsed as an integer, a real or a boolean
+    ///   (`true`/`false`, `yes`/`no`, `1`/`0`, case-insensitive);
+    /// * integers, reals and booleans are converted between each other
+    ///   when no information is lost (e.g. `2.0` becomes `2`, but `2.5` doesn't);
+    /// * integers, reals, booleans and keys are formatted as strings.
+    ///
+    /// A value of the same type is simply cloned. Returns [None] if
+    /// the coercion isn't sensible or the string isn't valid UTF-8.
+    pub fn coerce<'b>(&self, target: ValueType) -> Option<Value<'b>> {
+        if self.value_type() == target {
+            return Some(self.clone());
+        }
+        match (self, target) {
+            (Value::PString(s), _) => coerce_str(s.try_as_str().ok()?, target),
+            (Value::Key(key), ValueType::String) => Some(PString::new(key.get()).into()),
+            (Value::Integer(i), ValueType::Real) => {
+                let x = i.as_i128();
+                (x as f64 as i128 == x).then(|| Real::new(x as f64).into())
+            }
+            (Value::Integer(i), ValueType::Boolean) => match (i.is_negative(), i.as_unsinged()) {
+                (false, 0) => Some(false.into()),
+                (false, 1) => Some(true.into()),
+                _ => None,
+            },
+            (Value::Integer(i), ValueType::String) => Some(if i.is_negative() {
+                PString::new(i.as_singed().to_string()).into()
+            } else {
+                PString::new(i.as_unsinged().to_string()).into()
+            }),
+            (Value::Real(r), ValueType::Integer) => {
+                let r = r.as_float();
+                if r.fract() != 0.0 || !r.is_finite() {
+                    None
+                } else if r >= 0.0 && r < u64::MAX as f64 {
+                    Some((r as u64).into())
+                } else if r < 0.0 && r >= i64::MIN as f64 {
+                    Some((r as i64).into())
+                } else {
+                    None
+                }
+            }
+            (Value::Real(r), ValueType::String) => {
+                Some(PString::new(r.as_float().to_string()).into())
+            }
+            (Value::Boolean(b), ValueType::Integer) => Some((b.as_bool() as u64).into()),
+            (Value::Boolean(b), ValueType::Real) => {
+                Some(Real::new(b.as_bool() as u8 as f64).into())
+            }
+            (Value::Boolean(b), ValueType::String) => {
+                Some(PString::new(b.as_bool().to_string()).into())
+            }
+            _ => None,
+        }
+    }
+
     /// Returns `true` if the [Value] is a [Null].
     pub fn is_null(&self) -> bool {
         matches!(self, Value::Null(_))
     }
 
+    /// Builds a new tree containing only the nodes along the given
+    /// [string paths](PlistPath#string-paths) and the complete subtrees they
+    /// point to. Everything else is pruned.
+    ///
+    /// Array items keep their relative order, but their indices are compacted.
+    /// Paths that are malformed or don't exist in the tree are ignored.
+    /// If none of them match, an empty container of the same kind is returned
+    /// (or a [Null] node for scalar values).
+    pub fn project<'b>(&self, paths: &[&str]) -> Value<'b> {
+        let selected: Vec<PlistPath> = paths
+            .iter()
+            .filter_map(|path| path::parse_segments(path).ok())
+            .filter_map(|segments| path::resolve(self, &segments))
+            .collect();
+        let selected: Vec<&[PathComponent]> =
+            selected.iter().map(|path| path.components()).collect();
+        if selected.is_empty() {
+            return match self {
+                Value::Array(_) => Array::new().into(),
+                Value::Dictionary(_) => Dictionary::new().into(),
+                _ => Null::new().into(),
+            };
+        }
+        project_node(self, &selected)
+    }
+
+    /// Returns a mutable reference to the node at the given
+    /// [string path](PlistPath#string-paths).
+    ///
+    /// The returned [ItemMut] points to the node inside this tree, so any
+    /// change made through it (e.g. with [Value::replace_with] or setters of
+    /// the node types) is visible from the parent. On failure, the [PathError]
+    /// tells which segment couldn't be followed and why.
+    pub fn get_mut_path(&mut self, path: &str) -> Result<ItemMut<'_>, PathError> {
+        let pointer = path::lookup(self.pointer(), path)?;
+        let mut value = unsafe { from_pointer(pointer) };
+        value.as_node_mut().set_false_drop(true);
+        Ok(ItemMut(value))
+    }
+
+    /// Returns a reference to the node at the given path, or [None] if any
+    /// of the components doesn't exist.
+    ///
+    /// A [PathComponent::Key] only matches a dictionary and
+    /// a [PathComponent::Index] only matches an array. The [path!] macro is
+    /// a short way to build the components.
+    pub fn get_path(&self, path: &[PathComponent]) -> Option<Item<'_>> {
+        let pointer = path::lookup_components(self.pointer(), path)?;
+        Some(unsafe { iter::borrowed_item(pointer) })
+    }
+
+    /// Returns a mutable reference to the node at the given path, or [None]
+    /// if any of the components doesn't exist.
+    ///
+    /// The components are matched like in [Value::get_path]. The returned
+    /// [ItemMut] points to the node inside this tree, so scalar leaves can
+    /// be changed with [Value::replace_with] or the setters of the node types.
+    /// A path ending at an array or a dictionary still returns the node,
+    /// but [Value::replace_with] doesn't accept containers: use
+    /// [Array::set] or [Dictionary::insert] on it instead.
+    pub fn get_path_mut(&mut self, path: &[PathComponent]) -> Option<ItemMut<'_>> {
+        let pointer = path::lookup_components(self.pointer(), path)?;
+        let mut value = unsafe { from_pointer(pointer) };
+        value.as_node_mut().set_false_drop(true);
+        Some(ItemMut(value))
+    }
+
+    /// Returns a reference to the node at a precompiled path, or [None]
+    /// if the path can't be followed.
+    ///
+    /// The path is followed the same way as in [Value::get_mut_path],
+    /// but it isn't parsed again on every call.
+    pub fn get_compiled(&self, path: &CompiledPath) -> Option<Item<'_>> {
+        let pointer = path.lookup(self.pointer())?;
+        Some(unsafe { iter::borrowed_item(pointer) })
+    }
+
+    /// Returns a handle to the node at the given
+    /// [string path](PlistPath#string-paths) without copying it,
+    /// or [None] if the path can't be followed.
+    ///
+    /// The handle borrows this value, see [LazyValue] for details.
+    pub fn lazy_child(&self, path: &str) -> Option<LazyValue<'_>> {
+        let pointer = path::lookup(self.pointer(), path).ok()?;
+        Some(LazyValue::new(pointer))
+    }
+
+    /// If the value is an array with exactly one item or a dictionary with
+    /// exactly one entry, returns that inner value. Returns the value itself
+    /// otherwise.
+    ///
+    /// Only one level is unwrapped. Like the items of arrays and dictionaries,
+    /// the result is a reference, so it's returned as an [Item].
+    pub fn unwrap_singleton(&self) -> Item<'_> {
+        let inner = match self {
+            Value::Array(array) if array.len() == 1 => iter::children(array.pointer).pop(),
+            Value::Dictionary(dict) if dict.len() == 1 => iter::children(dict.pointer).pop(),
+            _ => None,
+        };
+        unsafe { iter::borrowed_item(inner.unwrap_or(self.pointer())) }
+    }
+
+    /// Returns the number of nodes in the tree, including the value itself.
+    ///
+    /// Dictionary keys aren't counted as separate nodes.
+    pub fn node_count(&self) -> u64 {
+        let mut count = 0;
+        let mut stack = vec![self.pointer()];
+        while let Some(pointer) = stack.pop() {
+            count += 1;
+            stack.extend(iter::children(pointer));
+        }
+        count
+    }
+
+    /// Returns `true` if the value itself or any node of its tree is equal
+    /// to `needle`.
+    ///
+    /// Whole arrays and dictionaries are compared too, so this finds
+    /// sub-structures as well as leaves. Dictionary keys aren't nodes,
+    /// so a [PString] needle doesn't match a key.
+    pub fn deep_contains(&self, needle: &Value) -> bool {
+        let mut stack = vec![self.pointer()];
+        while let Some(pointer) = stack.pop() {
+            let node = unsafe { iter::borrowed_item(pointer) };
+            if *node == *needle {
+                return true;
+            }
+            stack.extend(iter::children(pointer));
+        }
+        false
+    }
+
+    /// Returns the path of the node wrapped by `target` within the tree,
+    /// or [None] if the node isn't a part of it.
+    ///
+    /// The node is located by identity (see [Value::ptr_eq]), not by
+    /// equality, so it finds the exact node even if there are equal ones.
+    /// The value itself has an empty path.
+    pub fn path_of(&self, target: &Value) -> Option<PlistPath> {
+        find_node(self, target.pointer(), &mut PlistPath::new())
+    }
+
+    /// Replaces the node at the given [string path](PlistPath#string-paths)
+    /// with a new value, or inserts it if it doesn't exist yet.
+    ///
+    /// All segments but the last one must exist. The last one is either a key
+    /// of a dictionary (the entry is replaced or inserted) or an index of an
+    /// array: an existing item is replaced, and the index equal to the length
+    /// of the array appends the value. The path must point to a child node,
+    /// so an empty path is rejected as [PathErrorKind::Malformed].
+    pub fn set_path<'b>(
+        &mut self,
+        path: &str,
+        new_value: impl Into<Value<'b>>,
+    ) -> Result<(), PathError> {
+        let mut segments = path::parse_segments(path).map_err(|_| PathError::malformed(path))?;
+        let last = segments.pop().ok_or_else(|| PathError::malformed(path))?;
+        let (parent_pointer, parent) = path::lookup_segments(self.pointer(), segments)?;
+        let error = |kind| PathError {
+            parent: parent.clone(),
+            segment: last.clone(),
+            kind,
+        };
+        let mut parent_value = unsafe { from_pointer(parent_pointer) };
+        parent_value.as_node_mut().set_false_drop(true);
+        match &mut parent_value {
+            Value::Dictionary(dict) => {
+                if last.contains('\0') {
+                    return Err(error(PathErrorKind::Malformed));
+                }
+                dict.insert(last, new_value);
+            }
+            Value::Array(array) => {
+                let index = last
+                    .parse::<u32>()
+                    .map_err(|_| error(PathErrorKind::IndexOutOfBounds))?;
+                match index.cmp(&array.len()) {
+                    std::cmp::Ordering::Less => array.set(new_value, index),
+                    std::cmp::Ordering::Equal => array.append(new_value),
+                    std::cmp::Ordering::Greater => {
+                        return Err(error(PathErrorKind::IndexOutOfBounds));
+                    }
+                }
+            }
+            _ => return Err(error(PathErrorKind::NotAContainer)),
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator over the scalar leaves of the tree, skipping
+    /// arrays and dictionaries.
+    ///
+    /// The values are visited depth-first in document order. A scalar
+    /// value yields only itself.
+    pub fn scalars(&self) -> impl Iterator<Item = Item<'_>> {
+        iter::Scalars::new(self)
+    }
+
+    /// Visits every node of the tree depth-first in document order,
+    /// calling `f` with the path of the node and the node itself.
+    ///
+    /// Containers are visited before their children, and the value itself
+    /// comes first with an empty path.
+    ///
+    /// ```rust
+    /// use plist_plus2::{Value, array, dict};
+    ///
+    /// let plist: Value = dict!("name" => "pig", "tags" => array!("pink")).into();
+    /// let mut strings = Vec::new();
+    /// plist.walk(|path, node| {
+    ///     if let Some(string) = node.as_string() {
+    ///         strings.push((path.len(), string.as_str().to_string()));
+    ///     }
+    /// });
+    /// assert_eq!(strings, [(1, "pig".to_string()), (2, "pink".to_string())]);
+    /// ```
+    pub fn walk<F>(&self, mut f: F)
+    where
+        F: FnMut(&[PathComponent], &Value),
+    {
+        walk_node(self, &mut PlistPath::new(), &mut f);
+    }
+
+    /// Visits every node of the tree like [Value::walk], passing the nodes
+    /// mutably.
+    ///
+    /// Changes made through the reference are written to the tree, including
+    /// assigning a whole new value to a node other than the root. The children
+    /// of a node are visited after `f` returns, so they're the children of
+    /// the updated node.
+    pub fn walk_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[PathComponent], &mut Value),
+    {
+        walk_node_mut(self, &mut PlistPath::new(), &mut f);
+    }
+
+    /// Returns every [Data] node of the tree along with its path,
+    /// depth-first in document order.
+    ///
+    /// Like the items of arrays and dictionaries, the nodes are references,
+    /// so they're returned as [Item]s; [Value::as_data] always succeeds
+    /// on them. A data value yields only itself with an empty path.
+    pub fn data_nodes(&self) -> impl Iterator<Item = (PlistPath, Item<'_>)> {
+        let mut nodes = Vec::new();
+        self.walk(|path, node| {
+            if let Value::Data(data) = node {
+                nodes.push((PlistPath::from(path), data.pointer));
+            }
+        });
+        nodes
+            .into_iter()
+            .map(|(path, pointer)| (path, unsafe { iter::borrowed_item(pointer) }))
+    }
+
+    /// Checks that every string node of the tree is valid UTF-8.
+    ///
+    /// Parsed plists (binary ones in particular) may contain strings that
+    /// make [PString::as_str] panic. Call this right after parsing to fail
+    /// gracefully instead. Returns the path of the first invalid string.
+    /// Dictionary keys aren't checked since they're always decoded lossily.
+    pub fn validate_utf8(&self) -> Result<(), PlistPath> {
+        find_invalid_utf8(self, &mut PlistPath::new())
+    }
+
+    /// Returns paths to every integer node whose magnitude exceeds 2^53.
+    ///
+    /// JSON numbers are usually parsed as double precision floats, so such
+    /// integers silently lose precision in many JSON consumers. Use this
+    /// check before calling [Value::to_json] to decide whether they should be
+    /// stored as strings instead.
+    pub fn json_lossy_integers(&self) -> Vec<PlistPath> {
+        let mut paths = Vec::new();
+        self.walk(|path, node| {
+            let Value::Integer(integer) = node else {
+                return;
+            };
+            let magnitude = if integer.is_negative() {
+                integer.as_singed().unsigned_abs()
+            } else {
+                integer.as_unsinged()
+            };
+            if magnitude > JSON_MAX_SAFE_INTEGER {
+                paths.push(PlistPath::from(path));
+            }
+        });
+        paths
+    }
+
+    /// Checks that the tree can be exported with [Value::to_xml].
+    ///
+    /// XML plists have no representation for [Null] and [Uid] nodes or
+    /// for infinite and NaN reals. Returns the paths of all such nodes,
+    /// so the caller can report them before attempting the export.
+    pub fn xml_compatibility(&self) -> Result<(), Vec<PlistPath>> {
+        let mut paths = Vec::new();
+        self.walk(|path, node| match node {
+            Value::Null(_) | Value::Uid(_) => paths.push(PlistPath::from(path)),
+            Value::Real(real) if !real.is_finite() => paths.push(PlistPath::from(path)),
+            _ => {}
+        });
+        if paths.is_empty() { Ok(()) } else { Err(paths) }
+    }
+
     /// Replaces the current Value with another one.
     ///
     /// The `new_value` will be cloned (this is how the C library works).
@@ -453,8 +1021,235 @@ impl<'a> Value<'a> {
         new_self.as_node_mut().set_false_drop(false_drop);
         *self = new_self;
     }
+
+    /// Replaces every scalar leaf of the tree equal to `from` with a copy
+    /// of `to` and returns the number of replaced leaves.
+    ///
+    /// This is [Value::replace_with] applied to the whole tree, so only
+    /// scalars are supported: an array or a dictionary `from` never matches,
+    /// since containers aren't leaves.
+    ///
+    /// # Panics
+    /// Panics if `to` is an Array, Dictionary, Key or Null, like
+    /// [Value::replace_with] does.
+    pub fn replace_all(&mut self, from: &Value, to: &Value) -> u32 {
+        if matches!(
+            to,
+            Value::Array(_) | Value::Dictionary(_) | Value::Key(_) | Value::Null(_)
+        ) {
+            panic!("Replacing a plist node with a such value is not supported");
+        }
+        let matching: Vec<unsafe_bindings::plist_t> = self
+            .scalars()
+            .filter(|item| **item == *from)
+            .map(|item| item.pointer())
+            .collect();
+        for &pointer in &matching {
+            if pointer == self.pointer() {
+                self.replace_with(to);
+            } else {
+                let mut leaf = unsafe { from_pointer(pointer) };
+                leaf.as_node_mut().set_false_drop(true);
+                leaf.replace_with(to);
+            }
+        }
+        matching.len() as u32
+    }
+}
+
+/// Parses a string as a scalar of the `target` type for [Value::coerce].
+fn coerce_str<'b>(s: &str, target: ValueType) -> Option<Value<'b>> {
+    let trimmed = s.trim();
+    match target {
+        ValueType::Integer => {
+            if let Ok(unsigned) = trimmed.parse::<u64>() {
+                Some(unsigned.into())
+            } else {
+                trimmed.parse::<i64>().ok().map(Value::from)
+            }
+        }
+        ValueType::Real => trimmed.parse::<f64>().ok().map(|r| Real::new(r).into()),
+        ValueType::Boolean => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(true.into()),
+            "false" | "no" | "0" => Some(false.into()),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
+/// Copies the parts of `value` selected by the (already resolved) paths.
+fn project_node<'b>(value: &Value, selected: &[&[PathComponent]]) -> Value<'b> {
+    if selected.iter().any(|path| path.is_empty()) {
+        return value.clone();
+    }
+    let subpaths = |component: &PathComponent| -> Vec<&[PathComponent]> {
+        selected
+            .iter()
+            .filter(|path| &path[0] == component)
+            .map(|path| &path[1..])
+            .collect()
+    };
+    match value {
+        Value::Dictionary(dict) => {
+            let mut projected = Dictionary::new();
+            for (key, item) in dict.iter() {
+                let rest = subpaths(&PathComponent::Key(key.clone()));
+                if !rest.is_empty() {
+                    projected.insert(key, project_node(&item, &rest));
+                }
+            }
+            projected.into()
+        }
+        Value::Array(array) => {
+            let mut projected = Array::new();
+            for (i, item) in array.iter().enumerate() {
+                let rest = subpaths(&PathComponent::Index(i as u32));
+                if !rest.is_empty() {
+                    projected.append(project_node(&item, &rest));
+                }
+            }
+            projected.into()
+        }
+        // Resolved paths never go past a scalar node
+        _ => value.clone(),
+    }
+}
+
+fn find_invalid_utf8(value: &Value, path: &mut PlistPath) -> Result<(), PlistPath> {
+    match value {
+        Value::PString(string) if std::str::from_utf8(string.as_bytes()).is_err() => {
+            Err(path.clone())
+        }
+        Value::Array(array) => {
+            for (i, item) in array.iter().enumerate() {
+                path.push(i as u32);
+                find_invalid_utf8(&item, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        Value::Dictionary(dict) => {
+            for (key, item) in dict.iter() {
+                path.push(key);
+                find_invalid_utf8(&item, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn find_node(
+    value: &Value,
+    target: unsafe_bindings::plist_t,
+    path: &mut PlistPath,
+) -> Option<PlistPath> {
+    if value.pointer() == target {
+        return Some(path.clone());
+    }
+    match value {
+        Value::Array(array) => array.iter().enumerate().find_map(|(i, item)| {
+            path.push(i as u32);
+            let found = find_node(&item, target, path);
+            path.pop();
+            found
+        }),
+        Value::Dictionary(dict) => dict.iter().find_map(|(key, item)| {
+            path.push(key);
+            let found = find_node(&item, target, path);
+            path.pop();
+            found
+        }),
+        _ => None,
+    }
+}
+
+fn walk_node<F>(value: &Value, path: &mut PlistPath, f: &mut F)
+where
+    F: FnMut(&[PathComponent], &Value),
+{
+    f(path.components(), value);
+    match value {
+        Value::Array(array) => {
+            for (i, item) in array.iter().enumerate() {
+                path.push(i as u32);
+                walk_node(&item, path, f);
+                path.pop();
+            }
+        }
+        Value::Dictionary(dict) => {
+            for (key, item) in dict.iter() {
+                path.push(key);
+                walk_node(&item, path, f);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_node_mut<F>(value: &mut Value, path: &mut PlistPath, f: &mut F)
+where
+    F: FnMut(&[PathComponent], &mut Value),
+{
+    f(path.components(), value);
+    match value {
+        Value::Array(array) => {
+            // The callback may change the length of the array
+            let mut index = 0;
+            while index < array.len() {
+                let pointer =
+                    unsafe { unsafe_bindings::plist_array_get_item(array.pointer, index) };
+                path.push(index);
+                if let Some(child) = walk_child_mut(pointer, path, f) {
+                    array.set(child, index);
+                }
+                path.pop();
+                index += 1;
+            }
+        }
+        Value::Dictionary(dict) => {
+            let keys: Vec<String> = dict.iter().map(|(key, _)| key).collect();
+            for key in keys {
+                let key_c_string = CString::new(key.as_str()).unwrap();
+                let pointer = unsafe {
+                    unsafe_bindings::plist_dict_get_item(dict.pointer, key_c_string.as_ptr())
+                };
+                // The callback may have removed the entry
+                if pointer.is_null() {
+                    continue;
+                }
+                path.push(key.as_str());
+                if let Some(child) = walk_child_mut(pointer, path, f) {
+                    dict.insert(key, child);
+                }
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Walks a child node and returns a new value if the callback replaced it.
+fn walk_child_mut<'b, F>(
+    pointer: unsafe_bindings::plist_t,
+    path: &mut PlistPath,
+    f: &mut F,
+) -> Option<Value<'b>>
+where
+    F: FnMut(&[PathComponent], &mut Value),
+{
+    let mut child = unsafe { from_pointer(pointer) };
+    child.as_node_mut().set_false_drop(true);
+    walk_node_mut(&mut child, path, f);
+    types::replaced(child, pointer)
+}
+
+/// The largest integer magnitude a JSON double can represent exactly.
+const JSON_MAX_SAFE_INTEGER: u64 = 1 << 53;
+
 impl TryFrom<Value<'_>> for Vec<u8> {
     type Error = Error;
 
@@ -463,6 +1258,69 @@ impl TryFrom<Value<'_>> for Vec<u8> {
     }
 }
 
+impl TryFrom<&Value<'_>> for i64 {
+    type Error = Error;
+
+    /// Returns [Error::InvalidArg] if the value isn't an integer
+    /// or doesn't fit into an `i64`.
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(integer) if integer.is_signed() => Ok(integer.as_singed()),
+            _ => Err(Error::InvalidArg),
+        }
+    }
+}
+
+impl TryFrom<&Value<'_>> for u64 {
+    type Error = Error;
+
+    /// Returns [Error::InvalidArg] if the value isn't an integer or is negative.
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(integer) if !integer.is_negative() => Ok(integer.as_unsinged()),
+            _ => Err(Error::InvalidArg),
+        }
+    }
+}
+
+impl TryFrom<&Value<'_>> for f64 {
+    type Error = Error;
+
+    /// Returns [Error::InvalidArg] if the value isn't a real.
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Real(real) => Ok(real.as_float()),
+            _ => Err(Error::InvalidArg),
+        }
+    }
+}
+
+impl TryFrom<&Value<'_>> for bool {
+    type Error = Error;
+
+    /// Returns [Error::InvalidArg] if the value isn't a boolean.
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Boolean(boolean) => Ok(boolean.as_bool()),
+            _ => Err(Error::InvalidArg),
+        }
+    }
+}
+
+impl TryFrom<&Value<'_>> for String {
+    type Error = Error;
+
+    /// Returns [Error::InvalidArg] if the value isn't a string or a key,
+    /// or [Error::Parse] if the string isn't valid UTF-8.
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::PString(string) => Ok(string.try_as_str()?.to_string()),
+            Value::Key(key) => Ok(key.get()),
+            _ => Err(Error::InvalidArg),
+        }
+    }
+}
+
 // I couldn't implement the standart Clone trait because of lifetimes.
 // Cloned value must have a lifetime of a function caller, not
 // of the old value.
@@ -473,6 +1331,16 @@ impl Value<'_> {
         let pointer = unsafe { unsafe_bindings::plist_copy(self.as_node().pointer()) };
         unsafe { from_pointer(pointer) }
     }
+
+    /// Clones the value like [Value::clone], but returns [Error::NoMem]
+    /// instead of panicking if `libplist` fails to allocate the copy.
+    pub fn try_clone<'a>(&self) -> Result<Value<'a>, Error> {
+        let pointer = unsafe { unsafe_bindings::plist_copy(self.as_node().pointer()) };
+        if pointer.is_null() {
+            return Err(Error::NoMem);
+        }
+        Ok(unsafe { from_pointer(pointer) })
+    }
 }
 
 /*
@@ -501,59 +1369,61 @@ fn it_fails() {
 /// # Panics
 /// May panic if an incorrect pointer has been passed and it was recognized on the C side.
 pub unsafe fn from_pointer<'a>(pointer: unsafe_bindings::plist_t) -> Value<'a> {
-    let typ: NodeType = unsafe { unsafe_bindings::plist_get_node_type(pointer) }.into();
+    let typ: ValueType = unsafe { unsafe_bindings::plist_get_node_type(pointer) }.into();
     match typ {
-        NodeType::Array => Value::Array(Array {
+        ValueType::Array => Value::Array(Array {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
+            children: Default::default(),
         }),
-        NodeType::Boolean => Value::Boolean(Boolean {
+        ValueType::Boolean => Value::Boolean(Boolean {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::Data => Value::Data(Data {
+        ValueType::Data => Value::Data(Data {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::Date => Value::Date(Date {
+        ValueType::Date => Value::Date(Date {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::Dictionary => Value::Dictionary(Dictionary {
+        ValueType::Dictionary => Value::Dictionary(Dictionary {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
+            children: Default::default(),
         }),
-        NodeType::Integer => Value::Integer(Integer {
+        ValueType::Integer => Value::Integer(Integer {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::Key => Value::Key(Key {
+        ValueType::Key => Value::Key(Key {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::Null => Value::Null(Null {
+        ValueType::Null => Value::Null(Null {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::Real => Value::Real(Real {
+        ValueType::Real => Value::Real(Real {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::String => Value::PString(PString {
+        ValueType::String => Value::PString(PString {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
         }),
-        NodeType::Uid => Value::Uid(Uid {
+        ValueType::Uid => Value::Uid(Uid {
             pointer,
             false_drop: false,
             phantom: std::marker::PhantomData,
@@ -561,6 +1431,32 @@ pub unsafe fn from_pointer<'a>(pointer: unsafe_bindings::plist_t) -> Value<'a> {
     }
 }
 
+/// Wraps a C pointer into a plist value without taking ownership of it.
+///
+/// Unlike [from_pointer], dropping the returned value doesn't free the C node,
+/// so it's meant for nodes owned by the C side or by another wrapper.
+///
+/// # Safety
+/// The same rules as for [from_pointer] apply. Besides, the node must stay
+/// alive for as long as the returned value (or anything borrowed from it)
+/// is used: the lifetime `'a` isn't tied to the owner.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{Value, from_pointer_borrowed};
+///
+/// let owner: Value = "owned by someone else".into();
+/// let borrowed = unsafe { from_pointer_borrowed(owner.pointer()) };
+/// assert_eq!(borrowed, owner);
+/// drop(borrowed);
+/// assert_eq!(owner.as_string().unwrap().as_str(), "owned by someone else");
+/// ```
+pub unsafe fn from_pointer_borrowed<'a>(pointer: unsafe_bindings::plist_t) -> Value<'a> {
+    let mut value = unsafe { from_pointer(pointer) };
+    value.as_node_mut().set_false_drop(true);
+    value
+}
+
 /// Parses a JSON string and returns a [Value] struct representing a plist.
 pub fn from_json<'a>(json: impl Into<String>) -> Result<Value<'a>, Error> {
     let json = CString::new(json.into())?;
@@ -574,17 +1470,71 @@ pub fn from_json<'a>(json: impl Into<String>) -> Result<Value<'a>, Error> {
 }
 
 /// Parses an XML string and returns a [Value] struct representing a plist.
+///
+/// If parsing fails because of a mismatched, unterminated or unclosed tag,
+/// [Error::ParseAt] tells the offset of the tag, otherwise [Error::Parse]
+/// is returned.
 pub fn from_xml<'a>(xml: impl Into<String>) -> Result<Value<'a>, Error> {
-    let xml = CString::new(xml.into())?;
-    let xml_len: u32 = xml.as_bytes().len() as u32;
+    let xml = xml.into();
+    let xml_c_string = CString::new(xml.as_str())?;
+    let xml_len: u32 = xml.len() as u32;
     let mut plist_t = unsafe { std::mem::zeroed() };
-    let result = unsafe { unsafe_bindings::plist_from_xml(xml.as_ptr(), xml_len, &mut plist_t) };
+    let result =
+        unsafe { unsafe_bindings::plist_from_xml(xml_c_string.as_ptr(), xml_len, &mut plist_t) };
     if result != PLIST_ERROR_SUCCESS {
-        return Err(result.into());
+        return Err(locate_xml_error(result.into(), &xml));
     }
     Ok(unsafe { from_pointer(plist_t) })
 }
 
+/// Turns [Error::Parse] of an XML text into [Error::ParseAt] if the
+/// position of the problem can be found.
+fn locate_xml_error(error: Error, xml: &str) -> Error {
+    match error {
+        Error::Parse => {
+            xml_error_offset(xml).map_or(Error::Parse, |offset| Error::ParseAt { offset })
+        }
+        error => error,
+    }
+}
+
+/// Returns the byte offset of the first unterminated or mismatched tag
+/// of an XML text or, failing that, of the innermost unclosed one.
+fn xml_error_offset(xml: &str) -> Option<usize> {
+    let mut open: Vec<(&str, usize)> = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = xml[pos..].find('<').map(|i| pos + i) {
+        let rest = &xml[start..];
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let Some(end) = rest.find(terminator) else {
+            return Some(start);
+        };
+        pos = start + end + terminator.len();
+        let tag = &rest[1..end];
+        if terminator != ">" || tag.starts_with(['?', '!']) || tag.ends_with('/') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            match open.pop() {
+                Some((open_name, _)) if open_name == name.trim_end() => {}
+                _ => return Some(start),
+            }
+        } else {
+            match tag.split_whitespace().next() {
+                Some(name) if tag.starts_with(name) => open.push((name, start)),
+                _ => return Some(start),
+            }
+        }
+    }
+    open.last().map(|&(_, start)| start)
+}
+
 /// Parses a slice of bytes as a binary plist and returns a [Value] struct.
 pub fn from_binary<'a>(bytes: &[u8]) -> Result<Value<'a>, Error> {
     let mut plist_t = unsafe { std::mem::zeroed() };
@@ -598,8 +1548,14 @@ pub fn from_binary<'a>(bytes: &[u8]) -> Result<Value<'a>, Error> {
 }
 
 /// Parses OpenStep ASCII string and returns a [Value] struct representing a plist.
+///
+/// The typed literals of GNUstep are supported as well: `<*I123>` (integer),
+/// `<*R1.5>` (real), `<*BY>` or `<*BN>` (boolean) and
+/// `<*D2001-01-01 00:00:00 +0000>` (date). They can't be used as dictionary
+/// keys, which is reported as [Error::Parse].
 pub fn from_openstep<'a>(xml: impl Into<String>) -> Result<Value<'a>, Error> {
-    let openstep = CString::new(xml.into())?;
+    let preprocessed = gnustep::preprocess(&xml.into())?;
+    let openstep = CString::new(preprocessed.text.as_str())?;
     let openstep_len: u32 = openstep.as_bytes().len() as u32;
     let mut plist_t = unsafe { std::mem::zeroed() };
     let result = unsafe {
@@ -612,10 +1568,54 @@ pub fn from_openstep<'a>(xml: impl Into<String>) -> Result<Value<'a>, Error> {
     if result != PLIST_ERROR_SUCCESS {
         return Err(result.into());
     }
-    Ok(unsafe { from_pointer(plist_t) })
+    preprocessed.restore(unsafe { from_pointer(plist_t) })
+}
+
+/// Parses a plist at the start of a buffer that may have other data after it.
+/// Returns the parsed value and the unconsumed tail of the buffer.
+///
+/// The format is determined with [detect_format]. Whitespace following a text
+/// plist is consumed as well. The end of a JSON or OpenStep plist can only be
+/// found if its root is an array or a dictionary, otherwise [Error::Parse]
+/// is returned. A binary plist is located by its trailer, so the shortest
+/// prefix of the buffer that parses successfully is used.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{Value, dict, from_memory_rest};
+///
+/// let mut bytes = Value::from(dict!("a" => 1)).to_bytes().unwrap();
+/// bytes.extend_from_slice(b"EXTRA");
+/// let (value, rest) = from_memory_rest(&bytes).unwrap();
+/// assert_eq!(value, dict!("a" => 1).into());
+/// assert_eq!(rest, b"EXTRA");
+/// ```
+pub fn from_memory_rest<'a>(bytes: &[u8]) -> Result<(Value<'a>, &[u8]), Error> {
+    let format = detect_format(bytes).ok_or(Error::InvalidArg)?;
+    if format == PlistFormat::Binary {
+        let mut error = Error::Parse;
+        for len in format::binary_lengths(bytes) {
+            match from_binary(&bytes[..len]) {
+                Ok(value) => return Ok((value, &bytes[len..])),
+                Err(e) => error = e,
+            }
+        }
+        return Err(error);
+    }
+    let len = format::text_length(bytes, format).ok_or(Error::Parse)?;
+    let text = std::str::from_utf8(&bytes[..len]).map_err(|_| Error::Parse)?;
+    let value = match format {
+        PlistFormat::Xml => from_xml(text),
+        PlistFormat::Json => from_json(text),
+        _ => from_openstep(text),
+    }?;
+    Ok((value, &bytes[len..]))
 }
 
 /// Parses a slice of bytes, determines its plist format and returns a [Value] struct representing a plist.
+///
+/// Like [from_xml], reports the position of a broken tag of an XML input
+/// as [Error::ParseAt].
 pub fn from_memory<'a>(bytes: &[u8]) -> Result<Value<'a>, Error> {
     let mut plist_t = unsafe { std::mem::zeroed() };
     let result = unsafe {
@@ -627,17 +1627,93 @@ pub fn from_memory<'a>(bytes: &[u8]) -> Result<Value<'a>, Error> {
         )
     };
     if result != PLIST_ERROR_SUCCESS {
-        return Err(result.into());
+        return Err(match std::str::from_utf8(bytes) {
+            Ok(xml) if detect_format(bytes) == Some(PlistFormat::Xml) => {
+                locate_xml_error(result.into(), xml)
+            }
+            _ => result.into(),
+        });
     }
     Ok(unsafe { from_pointer(plist_t) })
 }
 
+/// Parses a slice of bytes like [from_memory] and also returns the raw
+/// `plist_format_t` code libplist reported for the input.
+///
+/// | Code | Format |
+/// |------|--------|
+/// | 0 | none (the format couldn't be determined) |
+/// | 1 | XML |
+/// | 2 | binary |
+/// | 3 | JSON |
+/// | 4 | OpenStep |
+/// | 10 | print (output only) |
+/// | 11 | limd (output only) |
+/// | 12 | plutil (output only) |
+///
+/// Only the input formats are reported by the parser, the rest of the table
+/// is listed for completeness. Codes that correspond to a [PlistFormat]
+/// can be converted with [PlistFormat::from_raw_code]. Future libplist
+/// versions may report codes that aren't listed here, so don't assume
+/// the table is exhaustive.
+pub fn from_memory_raw_format<'a>(bytes: &[u8]) -> Result<(Value<'a>, u32), Error> {
+    let mut plist_t = unsafe { std::mem::zeroed() };
+    let mut format = unsafe_bindings::plist_format_t_PLIST_FORMAT_NONE;
+    let result = unsafe {
+        unsafe_bindings::plist_from_memory(
+            bytes.as_ptr() as *mut _,
+            bytes.len() as u32,
+            &mut plist_t,
+            &mut format,
+        )
+    };
+    if result != PLIST_ERROR_SUCCESS {
+        return Err(result.into());
+    }
+    Ok((unsafe { from_pointer(plist_t) }, format))
+}
+
+/// Parses a slice of bytes like [from_memory], but only accepts the given formats.
+///
+/// The format is determined with [detect_format] and the input is then parsed
+/// with the parser of that format only, so a buffer can't be smuggled through
+/// as a different format than the one that has been checked.
+/// Returns [Error::Format] if the format isn't allowed and [Error::Parse]
+/// if it can't be determined.
+pub fn from_memory_restricted<'a>(
+    bytes: &[u8],
+    allowed: &[PlistFormat],
+) -> Result<Value<'a>, Error> {
+    let format = detect_format(bytes).ok_or(Error::Parse)?;
+    if !allowed.contains(&format) {
+        return Err(Error::Format);
+    }
+    let text = || String::from_utf8(bytes.to_vec()).map_err(|_| Error::Parse);
+    match format {
+        PlistFormat::Binary => from_binary(bytes),
+        PlistFormat::Xml => from_xml(text()?),
+        PlistFormat::Json => from_json(text()?),
+        PlistFormat::OpenStep => from_openstep(text()?),
+    }
+}
+
 /// Reads a file, determines its plist format and returns a [Value] struct representing a plist.
 pub fn from_file<'a>(path: impl AsRef<std::path::Path>) -> Result<Value<'a>, Error> {
     let bytes = std::fs::read(path).map_err(|_| Error::IO)?;
     from_memory(&bytes)
 }
 
+/// Reads a stream to the end, determines its plist format and returns
+/// a [Value] struct representing a plist.
+///
+/// The whole stream is buffered in memory before parsing.
+/// Read errors are reported as [Error::IO].
+pub fn from_reader<'a, R: std::io::Read>(mut reader: R) -> Result<Value<'a>, Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).map_err(|_| Error::IO)?;
+    from_memory(&bytes)
+}
+
 mod plist_ffi {
     /// A hidden trait for any node for dealing with false dropping
     pub trait PlistFFI {
@@ -650,3 +1726,618 @@ mod plist_ffi {
         fn set_false_drop(&mut self, value: bool);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn xml_compatibility() {
+        let plist: Value = dict!(
+            "name" => "pig",
+            "ref" => Uid::new(3),
+            "list" => array!(1.5, Null::new(), f64::INFINITY)
+        )
+        .into();
+        let paths: Vec<String> = plist
+            .xml_compatibility()
+            .unwrap_err()
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(paths, vec!["ref", "list/1", "list/2"]);
+
+        let fine: Value = dict!("list" => array!(1, "two", 3.0)).into();
+        assert_eq!(fine.xml_compatibility(), Ok(()));
+    }
+
+    #[test]
+    fn from_memory_rest() {
+        let plist: Value = dict!("list" => array!(1, "a)b"), "name" => "x").into();
+        // OpenStep reads scalars back as strings, so it gets a string-only tree
+        let strings: Value = dict!("list" => array!("1", "a)b"), "name" => "x").into();
+        for (format, plist) in [
+            (PlistFormat::Xml, &plist),
+            (PlistFormat::Json, &plist),
+            (PlistFormat::OpenStep, &strings),
+        ] {
+            let mut bytes = plist.to_format(format).unwrap();
+            bytes.extend_from_slice(b"\nEXTRA");
+            let (parsed, rest) = crate::from_memory_rest(&bytes).unwrap();
+            assert_eq!(&parsed, plist);
+            assert_eq!(rest, b"EXTRA");
+        }
+        let (_, rest) = crate::from_memory_rest(b"[1, 2]").unwrap();
+        assert!(rest.is_empty());
+        let (parsed, rest) = crate::from_memory_rest(b"{ \"my key\" = 1; } {}").unwrap();
+        assert_eq!(parsed, dict!("my key" => "1").into());
+        assert_eq!(rest, b"{}");
+        assert_eq!(
+            crate::from_memory_rest(b"{ a = (1, 2); ").unwrap_err(),
+            Error::Parse
+        );
+    }
+
+    #[test]
+    fn json_lossy_integers() {
+        let plist: Value = dict!(
+            "small" => 42,
+            "negative" => -7,
+            "list" => array!(1u64 << 53, u64::MAX, i64::MIN)
+        )
+        .into();
+        let paths: Vec<String> = plist
+            .json_lossy_integers()
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(paths, vec!["list/1", "list/2"]);
+
+        let small: Value = 12345.into();
+        assert!(small.json_lossy_integers().is_empty());
+    }
+
+    #[test]
+    fn coerce() {
+        let answer: Value = PString::new("42").into();
+        assert_eq!(answer.coerce(ValueType::Integer), Some(42.into()));
+        assert_eq!(answer.coerce(ValueType::Real), Some(Real::new(42.0).into()));
+
+        let pink: Value = PString::new("pink").into();
+        assert_eq!(pink.coerce(ValueType::Integer), None);
+        assert_eq!(pink.coerce(ValueType::Boolean), None);
+
+        let negative: Value = (-3).into();
+        assert_eq!(
+            negative.coerce(ValueType::String),
+            Some(PString::new("-3").into())
+        );
+        assert_eq!(negative.coerce(ValueType::Boolean), None);
+
+        let half: Value = Real::new(2.5).into();
+        assert_eq!(half.coerce(ValueType::Integer), None);
+        let exact: Value = (1u64 << 53).into();
+        assert_eq!(
+            exact.coerce(ValueType::Real),
+            Some(Real::new(2f64.powi(53)).into())
+        );
+        let inexact: Value = ((1u64 << 53) + 1).into();
+        assert_eq!(inexact.coerce(ValueType::Real), None);
+        let invalid: Value =
+            unsafe { from_pointer(unsafe_bindings::plist_new_string(c"4\xE9".as_ptr())) };
+        assert_eq!(invalid.coerce(ValueType::Integer), None);
+        let yes: Value = PString::new("YES").into();
+        assert_eq!(yes.coerce(ValueType::Boolean), Some(true.into()));
+        assert_eq!(yes.coerce(ValueType::Dictionary), None);
+    }
+
+    #[test]
+    fn scalars() {
+        let plist = from_file("./tests/binary.plist").unwrap();
+        assert_eq!(plist.scalars().count(), 12);
+        assert!(
+            plist
+                .scalars()
+                .all(|item| item.as_array().is_none() && item.as_dictionary().is_none())
+        );
+
+        let arr: Value = array!(1, array!(2, dict!("three" => 3)), 4).into();
+        let numbers: Vec<u64> = arr
+            .scalars()
+            .map(|item| item.as_integer().unwrap().as_unsinged())
+            .collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn write_to_writer() {
+        let plist: Value = dict!("name" => "x", "list" => array!(1, 2)).into();
+        let mut xml = Vec::new();
+        plist.write_xml(&mut xml).unwrap();
+        assert_eq!(xml, plist.to_xml().unwrap().into_bytes());
+        let mut json = Vec::new();
+        plist.write_json(&mut json, true).unwrap();
+        assert_eq!(json, plist.to_json(true).unwrap().into_bytes());
+        let mut openstep = Vec::new();
+        plist.write_openstep(&mut openstep, false).unwrap();
+        assert_eq!(openstep, plist.to_openstep(false).unwrap().into_bytes());
+
+        let dict = plist.as_dictionary().unwrap();
+        let mut bytes = Vec::new();
+        dict.write_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, dict.to_bytes().unwrap());
+
+        let mut full = [0u8; 4];
+        let error = plist.write_xml(&mut full.as_mut_slice()).unwrap_err();
+        assert_eq!(error, Error::IO);
+    }
+
+    #[test]
+    fn to_xml_fragment() {
+        let plist: Value = dict!("name" => "x", "list" => array!(1, 2)).into();
+        let fragment = plist.to_xml_fragment().unwrap();
+        assert!(!fragment.contains("<?xml"));
+        assert!(!fragment.contains("<!DOCTYPE"));
+        assert!(!fragment.contains("plist"));
+        assert!(fragment.starts_with("<dict>"));
+
+        let wrapped = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<plist version=\"1.0\">\n{fragment}\n</plist>\n"
+        );
+        assert_eq!(from_xml(wrapped).unwrap(), plist);
+    }
+
+    #[test]
+    fn get_mut_path() {
+        let mut plist: Value = dict!(
+            "Animals" => array!(dict!("Name" => "pig", "Legs" => 4))
+        )
+        .into();
+        plist
+            .get_mut_path("Animals/0/Legs")
+            .unwrap()
+            .as_integer_mut()
+            .unwrap()
+            .set_unsigned(3);
+        plist
+            .get_mut_path("/Animals/0/Name")
+            .unwrap()
+            .replace_with(&"lamb".into());
+        let expected: Value = dict!(
+            "Animals" => array!(dict!("Name" => "lamb", "Legs" => 3))
+        )
+        .into();
+        assert_eq!(plist, expected);
+
+        let error = plist.get_mut_path("Animals/1/Name").unwrap_err();
+        assert_eq!(error.kind, PathErrorKind::IndexOutOfBounds);
+        assert_eq!(error.segment, "1");
+        assert_eq!(error.parent.to_string(), "Animals");
+
+        let error = plist.get_mut_path("Animals/0/Legs/x").unwrap_err();
+        assert_eq!(error.kind, PathErrorKind::NotAContainer);
+        assert_eq!(error.parent.to_string(), "Animals/0/Legs");
+
+        let error = plist.get_mut_path("Plants").unwrap_err();
+        assert_eq!(error.kind, PathErrorKind::KeyNotFound);
+        assert_eq!(
+            plist.get_mut_path("Animals//0").unwrap_err().kind,
+            PathErrorKind::Malformed
+        );
+    }
+
+    #[test]
+    fn from_memory_restricted() {
+        let xml = std::fs::read("./tests/book.plist").unwrap();
+        assert_eq!(
+            crate::from_memory_restricted(&xml, &[PlistFormat::Binary]),
+            Err(Error::Format)
+        );
+        let plist = crate::from_memory_restricted(&xml, &[PlistFormat::Xml]).unwrap();
+        assert_eq!(plist, from_memory(&xml).unwrap());
+
+        let binary = std::fs::read("./tests/binary.plist").unwrap();
+        assert!(crate::from_memory_restricted(&binary, &[PlistFormat::Binary]).is_ok());
+        assert_eq!(
+            crate::from_memory_restricted(b"[1, 2]", &[PlistFormat::Binary, PlistFormat::Xml]),
+            Err(Error::Format)
+        );
+
+        let openstep = b"{ \"my key\" = 1; }";
+        assert_eq!(
+            crate::from_memory_restricted(openstep, &[PlistFormat::OpenStep]).unwrap(),
+            dict!("my key" => "1").into()
+        );
+        assert_eq!(
+            crate::from_memory_restricted(openstep, &[PlistFormat::Json]),
+            Err(Error::Format)
+        );
+    }
+
+    #[test]
+    fn to_json_sorted() {
+        let first: Value = dict!(
+            "b" => 2,
+            "a" => dict!("y" => true, "x" => false),
+            "c" => array!(dict!("2" => 2, "1" => 1))
+        )
+        .into();
+        let second: Value = dict!(
+            "c" => array!(dict!("1" => 1, "2" => 2)),
+            "a" => dict!("x" => false, "y" => true),
+            "b" => 2
+        )
+        .into();
+        assert_ne!(first.to_json(false), second.to_json(false));
+        assert_eq!(first.to_json_sorted(false), second.to_json_sorted(false));
+        assert_eq!(first.to_json_sorted(true), second.to_json_sorted(true));
+        assert_eq!(
+            first.to_json_sorted(false).unwrap(),
+            r#"{"a":{"x":false,"y":true},"b":2,"c":[{"1":1,"2":2}]}"#
+        );
+    }
+
+    #[test]
+    fn validate_utf8() {
+        let mut plist: Value = dict!("list" => array!("fine", "also fine")).into();
+        assert_eq!(plist.validate_utf8(), Ok(()));
+
+        let invalid =
+            unsafe { from_pointer(unsafe_bindings::plist_new_string(c"caf\xE9".as_ptr())) };
+        plist
+            .get_mut_path("list")
+            .unwrap()
+            .as_array_mut()
+            .unwrap()
+            .append(invalid);
+        let path = plist.validate_utf8().unwrap_err();
+        assert_eq!(path.to_string(), "list/2");
+    }
+
+    #[test]
+    fn try_clone() {
+        let plist: Value = dict!("list" => array!(1, "two")).into();
+        let copy = plist.try_clone().unwrap();
+        assert_eq!(copy, plist);
+        assert!(!copy.ptr_eq(&plist));
+    }
+
+    #[test]
+    fn try_from_value_ref() -> Result<(), Error> {
+        let plist: Value = dict!(
+            "signed" => -5,
+            "big" => u64::MAX,
+            "real" => 0.5,
+            "flag" => true,
+            "name" => "pig",
+            "bytes" => vec![1u8, 2]
+        )
+        .into();
+        let dict = plist.as_dictionary().unwrap();
+        let signed: i64 = (&dict["signed"]).try_into()?;
+        let big: u64 = (&dict["big"]).try_into()?;
+        let real: f64 = (&dict["real"]).try_into()?;
+        let flag: bool = (&dict["flag"]).try_into()?;
+        let name: String = (&dict["name"]).try_into()?;
+        let bytes = dict["bytes"].try_as_bytes()?;
+        assert_eq!((signed, big, real, flag), (-5, u64::MAX, 0.5, true));
+        assert_eq!((name.as_str(), bytes), ("pig", &[1u8, 2][..]));
+
+        assert_eq!(i64::try_from(&dict["big"]), Err(Error::InvalidArg));
+        assert_eq!(u64::try_from(&dict["signed"]), Err(Error::InvalidArg));
+        assert_eq!(f64::try_from(&dict["signed"]), Err(Error::InvalidArg));
+        assert_eq!(bool::try_from(&dict["name"]), Err(Error::InvalidArg));
+        assert_eq!(String::try_from(&dict["bytes"]), Err(Error::InvalidArg));
+        assert_eq!(dict["name"].try_as_bytes(), Err(Error::InvalidArg));
+        Ok(())
+    }
+
+    #[test]
+    fn xml_error_offset() {
+        let xml = "<plist><dict><key>a</key><string>x</strin></dict></plist>";
+        assert_eq!(super::xml_error_offset(xml), Some(34));
+        assert_eq!(super::xml_error_offset("<plist><array>"), Some(7));
+        assert_eq!(super::xml_error_offset("<plist><true/"), Some(7));
+        let xml = "<?xml?><!-- <a> --><plist><data><![CDATA[<]]></data><true/></plist>";
+        assert_eq!(super::xml_error_offset(xml), None);
+        assert_eq!(
+            from_xml("<plist><array>").unwrap_err(),
+            Error::ParseAt { offset: 7 }
+        );
+        assert_eq!(
+            from_memory(b"<plist><array>").unwrap_err(),
+            Error::ParseAt { offset: 7 }
+        );
+        assert!(Error::ParseAt { offset: 7 }.is_parse());
+        assert!(Error::Parse.is_parse());
+        assert!(!Error::Format.is_parse());
+    }
+
+    #[test]
+    fn walk() {
+        let plist: Value = dict!("a" => array!(1, dict!("b" => "x")), "c" => true).into();
+        let mut visited = Vec::new();
+        plist.walk(|path, node| {
+            let path: Vec<String> = path.iter().map(|c| c.to_string()).collect();
+            visited.push((path.join("/"), node.value_type()));
+        });
+        let expected = [
+            ("", ValueType::Dictionary),
+            ("a", ValueType::Array),
+            ("a/0", ValueType::Integer),
+            ("a/1", ValueType::Dictionary),
+            ("a/1/b", ValueType::String),
+            ("c", ValueType::Boolean),
+        ];
+        let expected: Vec<_> = expected.iter().map(|(p, t)| (p.to_string(), *t)).collect();
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn walk_mut() {
+        let mut plist: Value = dict!(
+            "user" => "pig",
+            "password" => "secret",
+            "nested" => array!("token", dict!("password" => "hidden"))
+        )
+        .into();
+        plist.walk_mut(|path, node| {
+            let is_password = path.last() == Some(&PathComponent::from("password"));
+            if is_password {
+                *node = Null::new().into();
+            } else if let Some(string) = node.as_string_mut() {
+                string.set(string.as_str().to_uppercase());
+            }
+        });
+        let expected: Value = dict!(
+            "user" => "PIG",
+            "password" => Null::new(),
+            "nested" => array!("TOKEN", dict!("password" => Null::new()))
+        )
+        .into();
+        assert_eq!(plist, expected);
+    }
+
+    #[test]
+    fn get_path_mut() {
+        let mut plist: Value = dict!("a" => array!(1, dict!("b" => "x"))).into();
+        plist
+            .get_path_mut(&path!["a", 1, "b"])
+            .unwrap()
+            .replace_with(&"y".into());
+        plist
+            .get_path_mut(&path!["a"])
+            .unwrap()
+            .as_array_mut()
+            .unwrap()
+            .set(2, 0);
+        assert_eq!(plist, dict!("a" => array!(2, dict!("b" => "y"))).into());
+        assert!(plist.get_path_mut(&path!["a", 2]).is_none());
+        assert!(plist.get_path_mut(&path!["a", "b"]).is_none());
+    }
+
+    #[test]
+    fn path_of() {
+        let plist: Value = dict!(
+            "a" => array!(1, dict!("b" => "x")),
+            "c" => array!(1, dict!("b" => "x"))
+        )
+        .into();
+        let nested = plist.get_path(&path!["c", 1, "b"]).unwrap();
+        assert_eq!(plist.path_of(&nested).unwrap().to_string(), "c/1/b");
+        assert_eq!(plist.path_of(&plist).unwrap().to_string(), "");
+
+        let copy = nested.clone();
+        assert_eq!(plist.path_of(&copy), None);
+    }
+
+    #[test]
+    fn ptr_eq() {
+        let plist: Value = array!(dict!("a" => 1), dict!("a" => 1)).into();
+        let array = plist.as_array().unwrap();
+        let (first, second) = (array.get(0).unwrap(), array.get(1).unwrap());
+        assert_eq!(*first, *second);
+        assert!(!first.ptr_eq(&second));
+        assert!(first.ptr_eq(&array.get(0).unwrap()));
+        assert!(array[1].ptr_eq(&second));
+        assert!(!plist.ptr_eq(&plist.clone()));
+    }
+
+    #[test]
+    fn into_boxed_node() {
+        let values: Vec<Value> = vec![
+            dict!("name" => "x").into(),
+            array!(1, 2).into(),
+            PString::new("hello").into(),
+        ];
+        let expected: Vec<String> = values.iter().map(|v| v.to_xml().unwrap()).collect();
+        let nodes: Vec<Box<dyn Node>> = values.into_iter().map(Value::into_boxed_node).collect();
+        for (node, xml) in nodes.iter().zip(expected) {
+            assert_eq!(node.to_xml().unwrap(), xml);
+        }
+    }
+
+    #[test]
+    fn unwrap_singleton() {
+        let wrapped: Value = array!(dict!("name" => "x", "count" => 3)).into();
+        let inner = wrapped.unwrap_singleton();
+        assert_eq!(*inner, Value::from(dict!("name" => "x", "count" => 3)));
+
+        let wrapped: Value = dict!("root" => array!(1, 2)).into();
+        assert_eq!(*wrapped.unwrap_singleton(), Value::from(array!(1, 2)));
+
+        let plain: Value = array!(1, 2).into();
+        assert_eq!(*plain.unwrap_singleton(), plain);
+        let scalar: Value = 5.into();
+        assert_eq!(*scalar.unwrap_singleton(), scalar);
+    }
+
+    #[test]
+    fn get_path() {
+        let plist: Value = dict!(
+            "Animals" => array!(dict!("Name" => "pig", "Legs" => 4))
+        )
+        .into();
+        let legs = plist.get_path(&path!["Animals", 0, "Legs"]).unwrap();
+        assert_eq!(legs.as_integer().unwrap().as_unsinged(), 4);
+        let key = String::from("Animals");
+        let animals = plist.get_path(&path![key]).unwrap();
+        assert_eq!(animals.as_array().unwrap().len(), 1);
+        assert_eq!(*plist.get_path(&[]).unwrap(), plist);
+
+        assert!(plist.get_path(&path!["Animals", 1]).is_none());
+        assert!(plist.get_path(&path!["Animals", "0"]).is_none());
+        assert!(plist.get_path(&path![0]).is_none());
+        assert!(plist.get_path(&path!["Plants"]).is_none());
+        assert!(plist.get_path(&path!["Animals", 0, "Name", 0]).is_none());
+    }
+
+    #[test]
+    fn try_from_value() -> Result<(), Error> {
+        let integer: Integer = Value::from(5).try_into()?;
+        assert_eq!(integer.as_unsinged(), 5);
+        let array: Array = Value::from(array!("pig")).try_into()?;
+        assert_eq!(array.len(), 1);
+        let string = PString::try_from(Value::from("pig"))?;
+        assert_eq!(string.as_str(), "pig");
+
+        assert_eq!(
+            Integer::try_from(Value::from("5")).unwrap_err(),
+            Error::Format
+        );
+        assert_eq!(
+            Dictionary::try_from(Value::from(array!())).unwrap_err(),
+            Error::Format
+        );
+        assert_eq!(
+            Null::try_from(Value::from(false)).unwrap_err(),
+            Error::Format
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_pointer_borrowed() {
+        let owner: Value = dict!("Animals" => array!("pig", "lamb")).into();
+        for _ in 0..3 {
+            let borrowed = unsafe { crate::from_pointer_borrowed(owner.pointer()) };
+            assert_eq!(borrowed, owner);
+        }
+        let copy = owner.clone();
+        assert_eq!(copy, dict!("Animals" => array!("pig", "lamb")).into());
+    }
+
+    #[test]
+    fn into_preferences_dict() {
+        let array: Value = array!("pig", "lamb").into();
+        let prefs = array.into_preferences_dict().unwrap();
+        assert_eq!(prefs, dict!(PREFERENCES_WRAP_KEY => array!("pig", "lamb")));
+
+        let dict: Value = dict!("Enabled" => true).into();
+        let prefs = dict.into_preferences_dict_with(None).unwrap();
+        assert_eq!(prefs, dict!("Enabled" => true));
+
+        let value: Value = 5.into();
+        let prefs = value.into_preferences_dict_with(Some("Count")).unwrap();
+        assert_eq!(prefs, dict!("Count" => 5));
+        let value: Value = 5.into();
+        assert_eq!(
+            value.into_preferences_dict_with(None).unwrap_err(),
+            Error::Format
+        );
+    }
+
+    #[test]
+    fn get_compiled() {
+        let path = CompiledPath::parse("/Animals/0/Name").unwrap();
+        let plists: Vec<Value> = ["pig", "lamb", "worm"]
+            .into_iter()
+            .map(|name| dict!("Animals" => array!(dict!("Name" => name))).into())
+            .collect();
+        let names: Vec<String> = plists
+            .iter()
+            .map(|plist| {
+                let name = plist.get_compiled(&path).unwrap();
+                name.as_string().unwrap().as_str().to_string()
+            })
+            .collect();
+        assert_eq!(names, ["pig", "lamb", "worm"]);
+
+        let empty: Value = dict!("Animals" => array!()).into();
+        assert!(empty.get_compiled(&path).is_none());
+        assert_eq!(path.to_string(), "/Animals/0/Name");
+        assert_eq!(CompiledPath::parse("a//b").unwrap_err(), Error::InvalidArg);
+    }
+
+    #[test]
+    fn lazy_child() {
+        let plist: Value = dict!("Animals" => array!(dict!("Name" => "pig"))).into();
+        let lazy = plist.lazy_child("Animals/0").unwrap();
+        assert_eq!(lazy.value_type(), ValueType::Dictionary);
+        assert!(plist.lazy_child("Animals/1").is_none());
+
+        let mut first = lazy.materialize();
+        let second = lazy.materialize();
+        first.as_dictionary_mut().unwrap().insert("Name", "lamb");
+        assert_eq!(second, dict!("Name" => "pig").into());
+        assert_eq!(*lazy.get(), second);
+        drop(plist);
+        assert_eq!(first, dict!("Name" => "lamb").into());
+    }
+
+    #[test]
+    fn set_path() {
+        let mut plist: Value = dict!(
+            "Animals" => array!(dict!("Name" => "pig", "Colors" => array!("pink")))
+        )
+        .into();
+        plist.set_path("Animals/0/Name", "lamb").unwrap();
+        plist.set_path("Animals/0/Legs", 4).unwrap();
+        plist.set_path("Animals/0/Colors/0", "black").unwrap();
+        plist.set_path("Animals/0/Colors/1", "white").unwrap();
+        plist
+            .set_path("Animals/1", dict!("Name" => "worm"))
+            .unwrap();
+
+        {
+            let name = plist.get_mut_path("Animals/0/Name").unwrap();
+            assert_eq!(name.as_string().unwrap().as_str(), "lamb");
+        }
+        let expected: Value = dict!(
+            "Animals" => array!(
+                dict!("Name" => "lamb", "Colors" => array!("black", "white"), "Legs" => 4),
+                dict!("Name" => "worm")
+            )
+        )
+        .into();
+        assert_eq!(plist, expected);
+
+        let error = plist.set_path("Animals/5", 1).unwrap_err();
+        assert_eq!(error.kind, PathErrorKind::IndexOutOfBounds);
+        let error = plist.set_path("Animals/0/Name/x", 1).unwrap_err();
+        assert_eq!(error.kind, PathErrorKind::NotAContainer);
+        let error = plist.set_path("Plants/0", 1).unwrap_err();
+        assert_eq!(error.kind, PathErrorKind::KeyNotFound);
+        assert_eq!(
+            plist.set_path("", 1).unwrap_err().kind,
+            PathErrorKind::Malformed
+        );
+    }
+
+    #[test]
+    fn project() {
+        let plist = from_file("./tests/xml-animals.plist").unwrap();
+        let projected = plist.project(&["AnimalColors/pig", "/AnimalSounds/Lisa", "Missing/key"]);
+        let expected: Value = dict!(
+            "AnimalColors" => dict!("pig" => "pink"),
+            "AnimalSounds" => dict!("Lisa" => "Why is the worm talking like a lamb?")
+        )
+        .into();
+        assert_eq!(projected, expected);
+
+        let arr: Value = array!(dict!("a" => 1, "b" => 2), 5, dict!("c" => 3)).into();
+        let projected = arr.project(&["2", "0/b"]);
+        let expected: Value = array!(dict!("b" => 2), dict!("c" => 3)).into();
+        assert_eq!(projected, expected);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{Error, Value};
+use std::time::{Duration, Instant};
+
+/// The approximate size of a `libplist` node: the tree node and its data.
+const NODE_SIZE: usize = 64;
+/// The approximate overhead of an array or dictionary entry.
+const ENTRY_SIZE: usize = std::mem::size_of::<usize>();
+
+/// Statistics of a parse made with [from_memory_timed].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMetrics {
+    /// The size of the input in bytes.
+    pub input_size: usize,
+    /// The number of nodes in the parsed tree, see [Value::node_count].
+    pub node_count: u64,
+    /// The time spent parsing.
+    pub elapsed: Duration,
+}
+
+/// Statistics of a serialization made with [Value::to_bytes_timed].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializeMetrics {
+    /// The size of the output in bytes.
+    pub output_size: usize,
+    /// The number of nodes in the serialized tree, see [Value::node_count].
+    pub node_count: u64,
+    /// The time spent serializing.
+    pub elapsed: Duration,
+}
+
+/// Parses a slice of bytes like [from_memory](crate::from_memory)
+/// and measures the parse.
+///
+/// Only the parse itself is timed, counting the nodes isn't included.
+pub fn from_memory_timed<'a>(bytes: &[u8]) -> Result<(Value<'a>, ParseMetrics), Error> {
+    let start = Instant::now();
+    let value = crate::from_memory(bytes)?;
+    let elapsed = start.elapsed();
+    let metrics = ParseMetrics {
+        input_size: bytes.len(),
+        node_count: value.node_count(),
+        elapsed,
+    };
+    Ok((value, metrics))
+}
+
+impl Value<'_> {
+    /// Exports the plist node as a binary encoded plist like [Value::to_bytes]
+    /// and measures the serialization.
+    ///
+    /// Only the serialization itself is timed, counting the nodes isn't included.
+    pub fn to_bytes_timed(&self) -> Result<(Vec<u8>, SerializeMetrics), Error> {
+        let start = Instant::now();
+        let bytes = self.to_bytes()?;
+        let elapsed = start.elapsed();
+        let metrics = SerializeMetrics {
+            output_size: bytes.len(),
+            node_count: self.node_count(),
+            elapsed,
+        };
+        Ok((bytes, metrics))
+    }
+
+    /// Returns a rough estimate of the memory in bytes the tree takes up,
+    /// without serializing it.
+    ///
+    /// Every node counts a fixed size plus the length of its string or data,
+    /// and every array item or dictionary entry adds a small overhead.
+    /// Dictionary keys count as nodes. The estimate isn't exact, but it only
+    /// depends on the contents of the tree, so equal trees have equal sizes.
+    pub fn memory_size(&self) -> usize {
+        let payload = match self {
+            Value::Array(array) => array
+                .iter()
+                .map(|item| ENTRY_SIZE + item.memory_size())
+                .sum(),
+            Value::Dictionary(dict) => dict
+                .iter()
+                .map(|(key, item)| ENTRY_SIZE + NODE_SIZE + key.len() + 1 + item.memory_size())
+                .sum(),
+            Value::PString(string) => string.as_bytes().len() + 1,
+            Value::Key(key) => key.get().len() + 1,
+            Value::Data(data) => data.as_bytes().len(),
+            _ => 0,
+        };
+        NODE_SIZE + payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn timed_parse_and_serialize() {
+        let bytes = std::fs::read("./tests/binary.plist").unwrap();
+        let (plist, metrics) = from_memory_timed(&bytes).unwrap();
+        assert_eq!(metrics.input_size, bytes.len());
+        assert_eq!(metrics.node_count, plist.node_count());
+        // The root, 13 entries and 2 lines
+        assert_eq!(metrics.node_count, 16);
+
+        let (output, metrics) = plist.to_bytes_timed().unwrap();
+        assert_eq!(metrics.output_size, output.len());
+        assert_eq!(metrics.node_count, plist.node_count());
+    }
+
+    #[test]
+    fn memory_size() {
+        let small: Value = dict!("name" => "pig", "legs" => 4).into();
+        let big: Value = dict!("name" => "pig", "legs" => 4, "data" => vec![0u8; 1000]).into();
+        assert_eq!(small.memory_size(), small.clone().memory_size());
+        assert!(big.memory_size() > small.memory_size() + 1000);
+        assert_eq!(
+            Value::from("abc").memory_size() - Value::from("").memory_size(),
+            3
+        );
+        assert_eq!(
+            Value::from(1).memory_size(),
+            Value::from(true).memory_size()
+        );
+    }
+}
@@ -0,0 +1,417 @@
+use crate::{Error, Value, ValueType, unsafe_bindings};
+use std::ffi::CString;
+use std::fmt;
+
+/// A single step of a [PlistPath]: either a dictionary key or an array index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathComponent {
+    /// A key of a dictionary entry.
+    Key(String),
+    /// An index of an array item.
+    Index(u32),
+}
+
+impl fmt::Display for PathComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathComponent::Key(key) => f.write_str(&key.replace('~', "~0").replace('/', "~1")),
+            PathComponent::Index(index) => index.fmt(f),
+        }
+    }
+}
+
+impl From<&str> for PathComponent {
+    fn from(value: &str) -> Self {
+        PathComponent::Key(value.to_string())
+    }
+}
+
+impl From<String> for PathComponent {
+    fn from(value: String) -> Self {
+        PathComponent::Key(value)
+    }
+}
+
+impl From<u32> for PathComponent {
+    fn from(value: u32) -> Self {
+        PathComponent::Index(value)
+    }
+}
+
+/// A location of a node inside a plist tree, starting from the root.
+///
+/// An empty path points to the root node itself. The path is displayed
+/// as its components separated by `/`, e.g. `Animals/0/Name`.
+///
+/// # String paths
+/// Methods such as [Value::project] accept paths written in the same form.
+/// Each segment is matched against the node it's applied to: it's a key
+/// for dictionaries and a decimal index for arrays. A leading `/` is optional
+/// and an empty string points to the root. Keys containing `/` or `~` are
+/// escaped as `~1` and `~0` respectively (like in JSON Pointer).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PlistPath {
+    components: Vec<PathComponent>,
+}
+
+impl PlistPath {
+    /// Creates an empty path pointing to the root node.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a component to the end of the path.
+    pub fn push(&mut self, component: impl Into<PathComponent>) {
+        self.components.push(component.into());
+    }
+
+    /// Removes the last component of the path and returns it,
+    /// or [None] if the path is empty.
+    pub fn pop(&mut self) -> Option<PathComponent> {
+        self.components.pop()
+    }
+
+    /// Returns the components of the path.
+    pub fn components(&self) -> &[PathComponent] {
+        &self.components
+    }
+
+    /// Returns the number of components in the path.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns `true` if the path points to the root node.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl From<Vec<PathComponent>> for PlistPath {
+    fn from(components: Vec<PathComponent>) -> Self {
+        Self { components }
+    }
+}
+
+impl From<&[PathComponent]> for PlistPath {
+    fn from(components: &[PathComponent]) -> Self {
+        Self {
+            components: components.to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for PlistPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            component.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The reason a [string path](PlistPath#string-paths) couldn't be followed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathErrorKind {
+    /// The path contains an empty segment or a malformed `~` escape.
+    Malformed,
+    /// The dictionary doesn't contain the key.
+    KeyNotFound,
+    /// The segment isn't a valid index or it's out of bounds.
+    IndexOutOfBounds,
+    /// The path goes through a node that is neither an array nor a dictionary.
+    NotAContainer,
+}
+
+/// An error returned when a [string path](PlistPath#string-paths) can't be followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathError {
+    /// The part of the path that has been successfully resolved.
+    pub parent: PlistPath,
+    /// The segment that failed.
+    pub segment: String,
+    /// What went wrong.
+    pub kind: PathErrorKind,
+}
+
+impl PathError {
+    pub(crate) fn malformed(path: &str) -> Self {
+        Self {
+            parent: PlistPath::new(),
+            segment: path.to_string(),
+            kind: PathErrorKind::Malformed,
+        }
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            PathErrorKind::Malformed => write!(f, "malformed path `{}`", self.segment),
+            PathErrorKind::KeyNotFound => {
+                write!(f, "key `{}` not found at `/{}`", self.segment, self.parent)
+            }
+            PathErrorKind::IndexOutOfBounds => write!(
+                f,
+                "index `{}` is invalid or out of bounds at `/{}`",
+                self.segment, self.parent
+            ),
+            PathErrorKind::NotAContainer => write!(
+                f,
+                "can't look up `{}`: `/{}` is neither an array nor a dictionary",
+                self.segment, self.parent
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+impl From<PathError> for Error {
+    fn from(_: PathError) -> Self {
+        Error::InvalidArg
+    }
+}
+
+/// Follows a string path starting at the given node and returns the pointer
+/// to the node it points to.
+pub(crate) fn lookup(
+    root: unsafe_bindings::plist_t,
+    path: &str,
+) -> Result<unsafe_bindings::plist_t, PathError> {
+    let segments = parse_segments(path).map_err(|_| PathError::malformed(path))?;
+    lookup_segments(root, segments).map(|(node, _)| node)
+}
+
+/// Follows already parsed segments starting at the given node and returns
+/// the pointer to the node they point to along with the resolved path.
+pub(crate) fn lookup_segments(
+    root: unsafe_bindings::plist_t,
+    segments: Vec<String>,
+) -> Result<(unsafe_bindings::plist_t, PlistPath), PathError> {
+    let mut parent = PlistPath::new();
+    let mut node = root;
+    for segment in segments {
+        let typ: ValueType = unsafe { unsafe_bindings::plist_get_node_type(node) }.into();
+        let error = |kind| PathError {
+            parent: parent.clone(),
+            segment: segment.clone(),
+            kind,
+        };
+        let child = match typ {
+            ValueType::Dictionary => {
+                let key = CString::new(segment.as_str())
+                    .map_err(|_| error(PathErrorKind::KeyNotFound))?;
+                unsafe { unsafe_bindings::plist_dict_get_item(node, key.as_ptr()) }
+            }
+            ValueType::Array => {
+                let index = segment
+                    .parse::<u32>()
+                    .map_err(|_| error(PathErrorKind::IndexOutOfBounds))?;
+                if index >= unsafe { unsafe_bindings::plist_array_get_size(node) } {
+                    return Err(error(PathErrorKind::IndexOutOfBounds));
+                }
+                unsafe { unsafe_bindings::plist_array_get_item(node, index) }
+            }
+            _ => return Err(error(PathErrorKind::NotAContainer)),
+        };
+        if child.is_null() {
+            return Err(error(match typ {
+                ValueType::Dictionary => PathErrorKind::KeyNotFound,
+                _ => PathErrorKind::IndexOutOfBounds,
+            }));
+        }
+        parent.push(match typ {
+            ValueType::Dictionary => PathComponent::Key(segment),
+            _ => PathComponent::Index(segment.parse().unwrap()),
+        });
+        node = child;
+    }
+    Ok((node, parent))
+}
+
+/// Follows typed components starting at the given node. Keys only match
+/// dictionaries and indices only match arrays.
+pub(crate) fn lookup_components(
+    root: unsafe_bindings::plist_t,
+    components: &[PathComponent],
+) -> Option<unsafe_bindings::plist_t> {
+    let mut node = root;
+    for component in components {
+        let typ: ValueType = unsafe { unsafe_bindings::plist_get_node_type(node) }.into();
+        node = match (component, typ) {
+            (PathComponent::Key(key), ValueType::Dictionary) => {
+                let key = CString::new(key.as_str()).ok()?;
+                unsafe { unsafe_bindings::plist_dict_get_item(node, key.as_ptr()) }
+            }
+            (PathComponent::Index(index), ValueType::Array) => {
+                if *index >= unsafe { unsafe_bindings::plist_array_get_size(node) } {
+                    return None;
+                }
+                unsafe { unsafe_bindings::plist_array_get_item(node, *index) }
+            }
+            _ => return None,
+        };
+        if node.is_null() {
+            return None;
+        }
+    }
+    Some(node)
+}
+
+/// A [string path](PlistPath#string-paths) parsed once to be looked up
+/// many times with [Value::get_compiled].
+///
+/// Every segment is prepared both as a dictionary key and as an array index
+/// in advance, so a lookup doesn't allocate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledPath {
+    source: String,
+    segments: Vec<CompiledSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompiledSegment {
+    /// [None] if the segment contains an internal 0 byte and can't be a key.
+    key: Option<CString>,
+    /// [None] if the segment isn't a decimal number.
+    index: Option<u32>,
+}
+
+impl CompiledPath {
+    /// Parses a string path.
+    ///
+    /// Returns [Error::InvalidArg] for empty segments and malformed escapes.
+    pub fn parse(path: &str) -> Result<Self, Error> {
+        let segments = parse_segments(path)?
+            .into_iter()
+            .map(|segment| CompiledSegment {
+                index: segment.parse().ok(),
+                key: CString::new(segment).ok(),
+            })
+            .collect();
+        Ok(Self {
+            source: path.to_string(),
+            segments,
+        })
+    }
+
+    /// Returns the path as it was given to [CompiledPath::parse].
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Follows the path starting at the given node and returns the pointer
+    /// to the node it points to.
+    pub(crate) fn lookup(
+        &self,
+        root: unsafe_bindings::plist_t,
+    ) -> Option<unsafe_bindings::plist_t> {
+        let mut node = root;
+        for segment in &self.segments {
+            let typ: ValueType = unsafe { unsafe_bindings::plist_get_node_type(node) }.into();
+            node = match typ {
+                ValueType::Dictionary => {
+                    let key = segment.key.as_ref()?;
+                    unsafe { unsafe_bindings::plist_dict_get_item(node, key.as_ptr()) }
+                }
+                ValueType::Array => {
+                    let index = segment.index?;
+                    if index >= unsafe { unsafe_bindings::plist_array_get_size(node) } {
+                        return None;
+                    }
+                    unsafe { unsafe_bindings::plist_array_get_item(node, index) }
+                }
+                _ => return None,
+            };
+            if node.is_null() {
+                return None;
+            }
+        }
+        Some(node)
+    }
+}
+
+impl fmt::Display for CompiledPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Splits a string path into unescaped segments.
+///
+/// Returns [Error::InvalidArg] for empty segments and malformed escapes.
+pub(crate) fn parse_segments(path: &str) -> Result<Vec<String>, Error> {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    if path.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut segments = Vec::new();
+    for raw in path.split('/') {
+        if raw.is_empty() {
+            return Err(Error::InvalidArg);
+        }
+        let mut segment = String::with_capacity(raw.len());
+        let mut chars = raw.chars();
+        while let Some(c) = chars.next() {
+            if c != '~' {
+                segment.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('0') => segment.push('~'),
+                Some('1') => segment.push('/'),
+                _ => return Err(Error::InvalidArg),
+            }
+        }
+        segments.push(segment);
+    }
+    Ok(segments)
+}
+
+/// Matches string segments against the tree, turning them into typed
+/// components. Returns [None] if any of the segments doesn't exist.
+pub(crate) fn resolve(value: &Value, segments: &[String]) -> Option<PlistPath> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Some(PlistPath::new());
+    };
+    let (component, mut tail) = match value {
+        Value::Dictionary(dict) => {
+            if segment.contains('\0') {
+                return None;
+            }
+            let child = dict.get(segment.as_str())?;
+            (PathComponent::Key(segment.clone()), resolve(&child, rest)?)
+        }
+        Value::Array(array) => {
+            let index = segment.parse::<u32>().ok()?;
+            let child = array.get(index)?;
+            (PathComponent::Index(index), resolve(&child, rest)?)
+        }
+        _ => return None,
+    };
+    tail.components.insert(0, component);
+    Some(tail)
+}
+
+/// A helper macro for building an array of [PathComponent]s.
+///
+/// String expressions become keys and integer ones become indices.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{Value, array, dict, path};
+///
+/// let plist: Value = dict!("Animals" => array!(dict!("Name" => "pig"))).into();
+/// let name = plist.get_path(&path!["Animals", 0, "Name"]).unwrap();
+/// assert_eq!(name.as_string().unwrap().as_str(), "pig");
+/// ```
+#[macro_export]
+macro_rules! path {
+    ($($component:expr),* $(,)?) => {
+        [$($crate::PathComponent::from($component)),*]
+    };
+}
@@ -0,0 +1,285 @@
+use crate::{Error, Value};
+use std::fmt;
+
+/// The size of the trailer that ends every binary plist.
+const TRAILER: usize = 32;
+/// The marker of an empty ASCII string, used in place of unreachable objects.
+const EMPTY_STRING: u8 = 0x50;
+
+/// A fix applied to a damaged binary plist by [from_binary_repair].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepairNote {
+    /// The size of the offset table entries was invalid and was
+    /// recomputed from the size of the table.
+    OffsetSize {
+        /// The size stored in the trailer.
+        found: u8,
+        /// The size used instead.
+        used: u8,
+    },
+    /// The size of object references was invalid and was recomputed
+    /// from the number of objects.
+    ObjectRefSize {
+        /// The size stored in the trailer.
+        found: u8,
+        /// The size used instead.
+        used: u8,
+    },
+    /// The number of objects didn't match the offset table and was
+    /// recomputed from its size.
+    ObjectCount {
+        /// The number stored in the trailer.
+        found: u64,
+        /// The number used instead.
+        used: u64,
+    },
+    /// The index of the top object was out of range, so the first object
+    /// is used as the root.
+    TopObject {
+        /// The index stored in the trailer.
+        found: u64,
+    },
+    /// An offset pointed outside of the objects, so the object was
+    /// replaced with an empty string.
+    Offset {
+        /// The index of the object.
+        index: u64,
+        /// The offset stored in the table.
+        found: u64,
+    },
+}
+
+impl fmt::Display for RepairNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepairNote::OffsetSize { found, used } => {
+                write!(f, "The offset size {found} was replaced with {used}")
+            }
+            RepairNote::ObjectRefSize { found, used } => {
+                write!(
+                    f,
+                    "The object reference size {found} was replaced with {used}"
+                )
+            }
+            RepairNote::ObjectCount { found, used } => {
+                write!(f, "The object count {found} was replaced with {used}")
+            }
+            RepairNote::TopObject { found } => {
+                write!(f, "The top object {found} was replaced with 0")
+            }
+            RepairNote::Offset { index, found } => write!(
+                f,
+                "The offset {found} of the object {index} is out of range, the object was replaced with an empty string"
+            ),
+        }
+    }
+}
+
+/// Parses a binary plist, trying to recover it if it's damaged.
+///
+/// If the plist parses as is, no notes are returned. Otherwise the trailer
+/// and the offset table are checked and the common kinds of damage are
+/// fixed:
+///
+/// - invalid sizes of offsets and object references are recomputed,
+/// - the number of objects is recomputed from the size of the offset table,
+/// - an out of range top object is replaced with the first one,
+/// - objects with out of range offsets are replaced with empty strings.
+///
+/// The fixed copy is parsed again and the applied fixes are returned along
+/// with the value. This is a best effort: the recovered tree may miss parts
+/// of the original one. Returns [Error::Parse] if the input isn't a binary
+/// plist, its offset table can't be located or it's still unreadable.
+pub fn from_binary_repair<'a>(bytes: &[u8]) -> Result<(Value<'a>, Vec<RepairNote>), Error> {
+    if bytes.len() < 8 + TRAILER || !bytes.starts_with(b"bplist0") {
+        return Err(Error::Parse);
+    }
+    if let Ok(value) = crate::from_binary(bytes) {
+        return Ok((value, Vec::new()));
+    }
+
+    let trailer = &bytes[bytes.len() - TRAILER..];
+    let number = |at: usize| u64::from_be_bytes(trailer[at..at + 8].try_into().unwrap());
+    let (offset_size, ref_size) = (trailer[6], trailer[7]);
+    let (count, top, table) = (number(8), number(16), number(24));
+    let table_end = (bytes.len() - TRAILER) as u64;
+    if !(8..=table_end).contains(&table) {
+        return Err(Error::Parse);
+    }
+    let table_len = table_end - table;
+    let valid_size = |size: u8| [1, 2, 4, 8].contains(&size);
+    let mut notes = Vec::new();
+
+    // The offset size must let the table hold the objects
+    let fits = |size: u8, count: u64| count.checked_mul(size as u64) == Some(table_len);
+    let mut used_offset_size = offset_size;
+    if !valid_size(offset_size) || !table_len.is_multiple_of(offset_size as u64) {
+        used_offset_size = [1, 2, 4, 8]
+            .into_iter()
+            .find(|&size| fits(size, count))
+            .or_else(|| {
+                [1, 2, 4, 8].into_iter().find(|&size| {
+                    table_len.is_multiple_of(size as u64)
+                        && read_offsets(bytes, table, table_len / size as u64, size)
+                            .iter()
+                            .all(|&offset| (8..table).contains(&offset))
+                })
+            })
+            .ok_or(Error::Parse)?;
+        notes.push(RepairNote::OffsetSize {
+            found: offset_size,
+            used: used_offset_size,
+        });
+    }
+    let mut used_count = count;
+    if !fits(used_offset_size, count) {
+        used_count = table_len / used_offset_size as u64;
+        notes.push(RepairNote::ObjectCount {
+            found: count,
+            used: used_count,
+        });
+    }
+    if used_count == 0 {
+        return Err(Error::Parse);
+    }
+    let mut used_ref_size = ref_size;
+    if !valid_size(ref_size) {
+        used_ref_size = byte_size(used_count - 1);
+        notes.push(RepairNote::ObjectRefSize {
+            found: ref_size,
+            used: used_ref_size,
+        });
+    }
+    let mut used_top = top;
+    if top >= used_count {
+        used_top = 0;
+        notes.push(RepairNote::TopObject { found: top });
+    }
+
+    // Unreachable objects point at an empty string appended to the objects
+    let mut offsets = read_offsets(bytes, table, used_count, used_offset_size);
+    let mut repaired = bytes[..table as usize].to_vec();
+    let mut filler = None;
+    for (index, offset) in offsets.iter_mut().enumerate() {
+        if !(8..table).contains(offset) {
+            notes.push(RepairNote::Offset {
+                index: index as u64,
+                found: *offset,
+            });
+            *offset = *filler.get_or_insert_with(|| {
+                repaired.push(EMPTY_STRING);
+                table
+            });
+        }
+    }
+
+    let table = repaired.len() as u64;
+    let used_offset_size = used_offset_size.max(byte_size(table));
+    for offset in offsets {
+        repaired.extend_from_slice(&offset.to_be_bytes()[8 - used_offset_size as usize..]);
+    }
+    repaired.extend_from_slice(&trailer[..6]);
+    repaired.extend_from_slice(&[used_offset_size, used_ref_size]);
+    for number in [used_count, used_top, table] {
+        repaired.extend_from_slice(&number.to_be_bytes());
+    }
+    let value = crate::from_binary(&repaired)?;
+    Ok((value, notes))
+}
+
+/// Reads `count` big endian offsets of `size` bytes starting at `table`.
+fn read_offsets(bytes: &[u8], table: u64, count: u64, size: u8) -> Vec<u64> {
+    bytes[table as usize..]
+        .chunks_exact(size as usize)
+        .take(count as usize)
+        .map(|chunk| chunk.iter().fold(0, |offset, &b| offset << 8 | b as u64))
+        .collect()
+}
+
+/// Returns the smallest of 1, 2, 4 and 8 bytes that can hold the number.
+fn byte_size(number: u64) -> u8 {
+    match number {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x10000..=0xFFFF_FFFF => 4,
+        _ => 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    /// Returns the sample binary plist and the position of its trailer.
+    fn sample() -> (Vec<u8>, usize) {
+        let bytes = std::fs::read("./tests/binary.plist").unwrap();
+        let trailer = bytes.len() - 32;
+        (bytes, trailer)
+    }
+
+    #[test]
+    fn repair_intact() {
+        let (bytes, _) = sample();
+        let (value, notes) = from_binary_repair(&bytes).unwrap();
+        assert!(notes.is_empty());
+        assert_eq!(value, from_binary(&bytes).unwrap());
+    }
+
+    #[test]
+    fn repair_trailer() {
+        let (mut bytes, trailer) = sample();
+        let original = from_binary(&bytes).unwrap();
+        bytes[trailer + 6] = 0;
+        bytes[trailer + 7] = 3;
+        bytes[trailer + 8..trailer + 16].copy_from_slice(&u64::MAX.to_be_bytes());
+        bytes[trailer + 16..trailer + 24].copy_from_slice(&100u64.to_be_bytes());
+        assert!(from_binary(&bytes).is_err());
+
+        let (value, notes) = from_binary_repair(&bytes).unwrap();
+        assert_eq!(value, original);
+        assert_eq!(
+            notes,
+            [
+                RepairNote::OffsetSize { found: 0, used: 2 },
+                RepairNote::ObjectCount {
+                    found: u64::MAX,
+                    used: 29
+                },
+                RepairNote::ObjectRefSize { found: 3, used: 1 },
+                RepairNote::TopObject { found: 100 },
+            ]
+        );
+    }
+
+    #[test]
+    fn repair_offset() {
+        let (mut bytes, trailer) = sample();
+        // Point the last object (the "Lines" key) past the offset table
+        bytes[trailer - 2..trailer].copy_from_slice(&0xFFFFu16.to_be_bytes());
+        assert!(from_binary(&bytes).is_err());
+
+        let (value, notes) = from_binary_repair(&bytes).unwrap();
+        assert_eq!(
+            notes,
+            [RepairNote::Offset {
+                index: 28,
+                found: 0xFFFF
+            }]
+        );
+        // The content that wasn't damaged is recovered
+        let dict = value.as_dictionary().unwrap();
+        assert_eq!(
+            dict["Author"].as_string().unwrap().as_str(),
+            "William Shakespeare"
+        );
+        assert_eq!(dict.len(), 13);
+    }
+
+    #[test]
+    fn repair_unrecoverable() {
+        assert_eq!(from_binary_repair(b"<plist/>").unwrap_err(), Error::Parse);
+        let (mut bytes, trailer) = sample();
+        bytes[trailer + 24..].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert_eq!(from_binary_repair(&bytes).unwrap_err(), Error::Parse);
+    }
+}
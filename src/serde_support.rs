@@ -0,0 +1,195 @@
+//! `serde` support for [Value].
+
+use crate::{Array, Boolean, Data, Dictionary, Integer, Null, PString, Real, Uid, Value};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Error as _, Serialize, SerializeMap, SerializeSeq, Serializer};
+
+/// The key of a map that represents a Uid.
+const UID_KEY: &str = "CF$UID";
+
+/// Values are mapped to the serde data model as follows:
+///
+/// | Plist | serde |
+/// |-------|-------|
+/// | Array | seq |
+/// | Dictionary | map with string keys |
+/// | Boolean | bool |
+/// | Integer | `u64` if it's [unsigned](crate::Integer::is_unsigned), `i64` otherwise |
+/// | Real | `f64` |
+/// | String, Key | string |
+/// | Data | bytes |
+/// | Date | RFC 3339 string in UTC, e.g. `2019-01-04T21:00:00Z` |
+/// | Uid | map `{"CF$UID": <u64>}` |
+/// | Null | unit |
+impl Serialize for Value<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Array(array) => {
+                let mut seq = serializer.serialize_seq(Some(array.len() as usize))?;
+                for item in array {
+                    seq.serialize_element(&*item)?;
+                }
+                seq.end()
+            }
+            Value::Dictionary(dict) => {
+                let mut map = serializer.serialize_map(Some(dict.len() as usize))?;
+                for (key, value) in dict {
+                    map.serialize_entry(&key, &*value)?;
+                }
+                map.end()
+            }
+            Value::Boolean(boolean) => serializer.serialize_bool(boolean.as_bool()),
+            Value::Integer(integer) if integer.is_unsigned() => {
+                serializer.serialize_u64(integer.as_unsinged())
+            }
+            Value::Integer(integer) => serializer.serialize_i64(integer.as_singed()),
+            Value::Real(real) => serializer.serialize_f64(real.as_float()),
+            Value::PString(string) => match std::str::from_utf8(string.as_bytes()) {
+                Ok(string) => serializer.serialize_str(string),
+                Err(e) => Err(S::Error::custom(e)),
+            },
+            Value::Key(key) => serializer.serialize_str(&key.get()),
+            Value::Data(data) => serializer.serialize_bytes(data.as_bytes()),
+            Value::Date(date) => serializer.serialize_str(&date.to_rfc3339()),
+            Value::Uid(uid) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(UID_KEY, &uid.get())?;
+                map.end()
+            }
+            Value::Null(_) => serializer.serialize_unit(),
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value<'static>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a plist value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Boolean::new(v).into())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Integer::new_signed(v).into())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Integer::new_unsigned(v).into())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Real::new(v).into())
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        PString::try_new(v)
+            .map(Into::into)
+            .map_err(|_| E::custom("strings can't contain an internal 0 byte"))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Data::new(v).into())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Null::new().into())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Null::new().into())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut array = Array::new();
+        while let Some(item) = seq.next_element::<Value>()? {
+            array.append(item);
+        }
+        Ok(array.into())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut dict = Dictionary::new();
+        while let Some((key, value)) = map.next_entry::<String, Value>()? {
+            if key.contains('\0') {
+                return Err(de::Error::custom("keys can't contain an internal 0 byte"));
+            }
+            dict.insert(key, value);
+        }
+        if dict.len() == 1
+            && let Some(uid) = dict.get(UID_KEY)
+            && let Some(uid) = uid.as_integer().filter(|uid| !uid.is_negative())
+        {
+            return Ok(Uid::new(uid.as_unsinged()).into());
+        }
+        Ok(dict.into())
+    }
+}
+
+/// Follows the mapping of the [Serialize] implementation backwards, with
+/// a few exceptions: strings always become plist strings (dates aren't
+/// recognized), `None` becomes Null, `Some` is unwrapped, and a map consisting
+/// of a single `CF$UID` unsigned integer becomes a Uid.
+impl<'de> Deserialize<'de> for Value<'_> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::time::Duration;
+
+    #[test]
+    fn serialize_to_json() {
+        let plist: Value = dict!(
+            "Name" => "pig",
+            "Legs" => 4,
+            "Weight" => 95.5,
+            "Tame" => true,
+            "Born" => Duration::from_secs(1546635600),
+            "Id" => Uid::new(7),
+            "Owner" => Null::new(),
+            "Photo" => Data::new(&[1, 2]),
+            "Big" => u64::MAX
+        )
+        .into();
+        let json = serde_json::to_value(&plist).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "Name": "pig",
+                "Legs": 4,
+                "Weight": 95.5,
+                "Tame": true,
+                "Born": "2019-01-04T21:00:00Z",
+                "Id": {"CF$UID": 7},
+                "Owner": null,
+                "Photo": [1, 2],
+                "Big": u64::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_from_json() {
+        let json = r#"{"Animals": ["pig", -1, 1.5, null], "Id": {"CF$UID": 3}}"#;
+        let plist: Value = serde_json::from_str(json).unwrap();
+        let expected: Value = dict!(
+            "Animals" => array!("pig", -1, 1.5, Null::new()),
+            "Id" => Uid::new(3)
+        )
+        .into();
+        assert_eq!(plist, expected);
+        assert!(serde_json::from_str::<Value>(r#"{"a\u0000": 1}"#).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{PathComponent, PlistPath, Value};
+use std::path::Path;
+
+/// The environment variable that makes [Value::assert_matches_snapshot]
+/// overwrite the stored snapshots instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "PLIST_PLUS2_UPDATE_SNAPSHOTS";
+
+impl Value<'_> {
+    /// Compares the value against a plist stored at `path`.
+    ///
+    /// The snapshot can be in any format supported by [from_file](crate::from_file)
+    /// and is compared logically, so formatting differences don't matter.
+    /// On mismatch, the error lists every differing node, one per line:
+    /// `-` marks a node missing from the value, `+` marks an unexpected one
+    /// and `~` marks a changed one.
+    ///
+    /// If the [UPDATE_SNAPSHOTS_VAR] environment variable is set to a non-empty
+    /// value, the value is written to `path` as XML instead and the comparison
+    /// is skipped.
+    pub fn assert_matches_snapshot(&self, path: &Path) -> Result<(), String> {
+        if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some_and(|var| !var.is_empty()) {
+            let xml = self
+                .to_xml()
+                .map_err(|e| format!("can't export the value: {e}"))?;
+            return std::fs::write(path, xml)
+                .map_err(|e| format!("can't write snapshot `{}`: {e}", path.display()));
+        }
+        let snapshot = crate::from_file(path).map_err(|e| {
+            format!(
+                "can't read snapshot `{}`: {e} (set {UPDATE_SNAPSHOTS_VAR}=1 to create it)",
+                path.display()
+            )
+        })?;
+        let mut lines = Vec::new();
+        diff(&snapshot, self, &mut PlistPath::new(), &mut lines);
+        if lines.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "value doesn't match snapshot `{}`:\n{}",
+            path.display(),
+            lines.join("\n")
+        ))
+    }
+}
+
+/// Collects the differences between two trees as human-readable lines.
+fn diff(expected: &Value, actual: &Value, path: &mut PlistPath, lines: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Dictionary(expected), Value::Dictionary(actual)) => {
+            for (key, expected) in expected {
+                path.push(key.as_str());
+                match actual.get(key.as_str()) {
+                    Some(actual) => diff(&expected, &actual, path, lines),
+                    None => lines.push(format!("- /{path}: {:?}", *expected)),
+                }
+                path.pop();
+            }
+            for (key, actual) in actual {
+                if expected.get(key.as_str()).is_none() {
+                    path.push(key.as_str());
+                    lines.push(format!("+ /{path}: {:?}", *actual));
+                    path.pop();
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for index in 0..expected.len().max(actual.len()) {
+                path.push(PathComponent::Index(index));
+                match (expected.get(index), actual.get(index)) {
+                    (Some(expected), Some(actual)) => diff(&expected, &actual, path, lines),
+                    (Some(expected), None) => lines.push(format!("- /{path}: {:?}", *expected)),
+                    (None, Some(actual)) => lines.push(format!("+ /{path}: {:?}", *actual)),
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        _ if expected != actual => {
+            lines.push(format!(
+                "~ /{path}: expected {expected:?}, found {actual:?}"
+            ));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn snapshot() {
+        let path = std::env::temp_dir().join("plist_plus2_snapshot.plist");
+        let stored: Value = dict!("Name" => "pig", "Colors" => array!("pink")).into();
+        std::fs::write(&path, stored.to_xml().unwrap()).unwrap();
+
+        let same: Value = dict!("Colors" => array!("pink"), "Name" => "pig").into();
+        assert_eq!(same.assert_matches_snapshot(&path), Ok(()));
+
+        let changed: Value = dict!("Name" => "lamb", "Colors" => array!(), "Legs" => 4).into();
+        let report = changed.assert_matches_snapshot(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(report.contains("~ /Name: expected"), "{report}");
+        assert!(report.contains("- /Colors/0: "), "{report}");
+        assert!(report.contains("+ /Legs: "), "{report}");
+    }
+}
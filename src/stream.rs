@@ -0,0 +1,175 @@
+use crate::{Array, Error, Node, PlistFormat, Value};
+use std::io::Write;
+
+const XML_OPENING: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+    <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+    \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+    <plist version=\"1.0\">\n<array>\n";
+const XML_CLOSING: &str = "</array>\n</plist>\n";
+
+/// Writes a root array element by element, without building it in memory.
+///
+/// Only [PlistFormat::Xml] and [PlistFormat::Json] can be streamed. The
+/// opening of the array is written on creation, every [push](Self::push)
+/// serializes one element right away, and [finish](Self::finish) writes
+/// the closing. Dropping the writer without finishing leaves the output
+/// incomplete.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{ArrayStreamWriter, PlistFormat};
+///
+/// let mut stream = ArrayStreamWriter::new(Vec::new(), PlistFormat::Json).unwrap();
+/// for i in 0..3 {
+///     stream.push(i).unwrap();
+/// }
+/// let json = stream.finish().unwrap();
+/// assert_eq!(json, b"[0,1,2]");
+/// ```
+#[derive(Debug)]
+pub struct ArrayStreamWriter<W: Write> {
+    writer: W,
+    format: PlistFormat,
+    len: u64,
+}
+
+impl<W: Write> ArrayStreamWriter<W> {
+    /// Writes the opening of an array in the given format.
+    ///
+    /// Returns [Error::InvalidArg] for formats that can't be streamed
+    /// and [Error::IO] if the writer fails.
+    pub fn new(mut writer: W, format: PlistFormat) -> Result<Self, Error> {
+        let opening = match format {
+            PlistFormat::Xml => XML_OPENING,
+            PlistFormat::Json => "[",
+            PlistFormat::Binary | PlistFormat::OpenStep => return Err(Error::InvalidArg),
+        };
+        writer
+            .write_all(opening.as_bytes())
+            .map_err(|_| Error::IO)?;
+        Ok(Self {
+            writer,
+            format,
+            len: 0,
+        })
+    }
+
+    /// Serializes the value as the next element of the array.
+    pub fn push<'b>(&mut self, value: impl Into<Value<'b>>) -> Result<(), Error> {
+        let value = value.into();
+        let element = match self.format {
+            // The fragment is written as is: re-indenting it would change
+            // the contents of multiline strings
+            PlistFormat::Xml => format!("{}\n", value.to_xml_fragment()?),
+            _ => {
+                // Wrapping the value lets libplist export scalars as well
+                let mut wrapper = Array::new();
+                wrapper.append(value);
+                let json = wrapper.to_json(false)?;
+                let element = json
+                    .trim()
+                    .strip_prefix('[')
+                    .and_then(|json| json.strip_suffix(']'))
+                    .ok_or(Error::Unknown)?;
+                if self.len == 0 {
+                    element.to_string()
+                } else {
+                    format!(",{element}")
+                }
+            }
+        };
+        self.writer
+            .write_all(element.as_bytes())
+            .map_err(|_| Error::IO)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the number of elements written so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no elements have been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Writes the closing of the array, flushes the writer and returns it.
+    pub fn finish(mut self) -> Result<W, Error> {
+        let closing = match self.format {
+            PlistFormat::Xml => XML_CLOSING,
+            _ => "]",
+        };
+        self.writer
+            .write_all(closing.as_bytes())
+            .and_then(|_| self.writer.flush())
+            .map_err(|_| Error::IO)?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn stream_integers() {
+        for format in [PlistFormat::Xml, PlistFormat::Json] {
+            let mut stream = ArrayStreamWriter::new(Vec::new(), format).unwrap();
+            for i in 0..1000 {
+                stream.push(i).unwrap();
+            }
+            assert_eq!(stream.len(), 1000);
+            let bytes = stream.finish().unwrap();
+
+            let parsed = from_memory(&bytes).unwrap();
+            let array = parsed.as_array().unwrap();
+            assert_eq!(array.len(), 1000);
+            assert!(
+                array
+                    .iter()
+                    .enumerate()
+                    .all(|(i, item)| item.as_integer().unwrap().as_singed() == i as i64)
+            );
+        }
+    }
+
+    #[test]
+    fn stream_nested_and_empty() {
+        for format in [PlistFormat::Xml, PlistFormat::Json] {
+            let empty = ArrayStreamWriter::new(Vec::new(), format).unwrap();
+            let text = String::from_utf8(empty.finish().unwrap()).unwrap();
+            let parsed = match format {
+                PlistFormat::Xml => from_xml(text),
+                _ => from_json(text),
+            };
+            assert_eq!(parsed.unwrap(), Value::from(array!()));
+
+            let mut stream = ArrayStreamWriter::new(Vec::new(), format).unwrap();
+            stream.push(dict!("name" => "pig", "legs" => 4)).unwrap();
+            stream.push(array!("a", true)).unwrap();
+            let parsed = from_memory(&stream.finish().unwrap()).unwrap();
+            let expected = array!(dict!("name" => "pig", "legs" => 4), array!("a", true));
+            assert_eq!(parsed, Value::from(expected));
+        }
+        assert_eq!(
+            ArrayStreamWriter::new(Vec::new(), PlistFormat::Binary).unwrap_err(),
+            Error::InvalidArg
+        );
+    }
+
+    #[test]
+    fn stream_xml_multiline_string() {
+        let text = "line1\nline2\r\nline3";
+        let mut stream = ArrayStreamWriter::new(Vec::new(), PlistFormat::Xml).unwrap();
+        stream.push(text).unwrap();
+        stream.push(array!(text)).unwrap();
+        let bytes = stream.finish().unwrap();
+
+        let parsed = from_memory(&bytes).unwrap();
+        let array = parsed.as_array().unwrap();
+        assert_eq!(array[0].as_string().unwrap().as_str(), text);
+        assert_eq!(array[1], array!(text).into());
+    }
+}
@@ -0,0 +1,104 @@
+//! TOML export of [Value].
+
+use crate::{Error, Value};
+use toml::value::{Array, Datetime, Table};
+
+impl Value<'_> {
+    /// Exports the plist node as a TOML document.
+    ///
+    /// The value must be a dictionary, since a TOML document is a table.
+    /// The nodes are mapped as follows:
+    ///
+    /// | Plist | TOML |
+    /// |-------|------|
+    /// | Dictionary | table |
+    /// | Array | array |
+    /// | Boolean | boolean |
+    /// | Integer | integer |
+    /// | Real | float |
+    /// | String, Key | string |
+    /// | Date | offset date-time in UTC |
+    ///
+    /// Returns [Error::Format] if the root isn't a dictionary or the tree
+    /// contains data, uid or null nodes, or integers above [i64::MAX],
+    /// which TOML can't represent.
+    pub fn to_toml(&self) -> Result<String, Error> {
+        let Value::Dictionary(_) = self else {
+            return Err(Error::Format);
+        };
+        let toml::Value::Table(table) = to_toml_value(self)? else {
+            unreachable!("a dictionary becomes a table");
+        };
+        toml::to_string(&table).map_err(|_| Error::Format)
+    }
+}
+
+fn to_toml_value(value: &Value) -> Result<toml::Value, Error> {
+    Ok(match value {
+        Value::Dictionary(dict) => {
+            let mut table = Table::new();
+            for (key, item) in dict {
+                table.insert(key, to_toml_value(&item)?);
+            }
+            toml::Value::Table(table)
+        }
+        Value::Array(array) => toml::Value::Array(
+            array
+                .iter()
+                .map(|item| to_toml_value(&item))
+                .collect::<Result<Array, Error>>()?,
+        ),
+        Value::Boolean(boolean) => toml::Value::Boolean(boolean.as_bool()),
+        Value::Integer(integer) if integer.is_unsigned() => return Err(Error::Format),
+        Value::Integer(integer) => toml::Value::Integer(integer.as_singed()),
+        Value::Real(real) => toml::Value::Float(real.as_float()),
+        Value::PString(string) => toml::Value::String(string.try_as_str()?.to_string()),
+        Value::Key(key) => toml::Value::String(key.get()),
+        Value::Date(date) => {
+            let datetime: Datetime = date.to_rfc3339().parse().map_err(|_| Error::Format)?;
+            toml::Value::Datetime(datetime)
+        }
+        Value::Data(_) | Value::Uid(_) | Value::Null(_) => return Err(Error::Format),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::time::Duration;
+
+    #[test]
+    fn to_toml() {
+        let plist: Value = dict!(
+            "name" => "pig",
+            "legs" => 4,
+            "born" => Duration::from_secs(1546635600),
+            "colors" => array!("pink", "grey"),
+            "size" => dict!("height" => 0.9)
+        )
+        .into();
+        let parsed: toml::Table = plist.to_toml().unwrap().parse().unwrap();
+        assert_eq!(parsed["name"].as_str(), Some("pig"));
+        assert_eq!(parsed["legs"].as_integer(), Some(4));
+        assert_eq!(
+            parsed["born"].as_datetime().unwrap().to_string(),
+            "2019-01-04T21:00:00Z"
+        );
+        assert_eq!(parsed["colors"][1].as_str(), Some("grey"));
+        assert_eq!(parsed["size"]["height"].as_float(), Some(0.9));
+    }
+
+    #[test]
+    fn to_toml_unsupported() {
+        assert_eq!(Value::from(array!(1)).to_toml(), Err(Error::Format));
+        for value in [
+            Value::from(vec![1u8]),
+            Uid::new(1).into(),
+            Null::new().into(),
+            u64::MAX.into(),
+        ] {
+            let plist: Value = dict!("list" => array!(value)).into();
+            assert_eq!(plist.to_toml(), Err(Error::Format));
+        }
+    }
+}
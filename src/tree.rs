@@ -0,0 +1,173 @@
+use crate::Value;
+use std::fmt::Write;
+
+/// The environment variable that disables the colors of
+/// [Value::to_colored_tree] when set to a non-empty value, see <https://no-color.org>.
+pub const NO_COLOR_VAR: &str = "NO_COLOR";
+
+/// The number of data bytes shown before the rest is elided.
+const DATA_PREVIEW: usize = 16;
+
+const RESET: &str = "\x1b[0m";
+const KEY_COLOR: &str = "\x1b[34m";
+const STRING_COLOR: &str = "\x1b[32m";
+const NUMBER_COLOR: &str = "\x1b[33m";
+const DATA_COLOR: &str = "\x1b[36m";
+const OTHER_COLOR: &str = "\x1b[35m";
+
+impl Value<'_> {
+    /// Renders the tree in a human-readable form for terminal display.
+    ///
+    /// Every node takes a line and children are indented by two spaces.
+    /// Strings and keys are quoted, data is shown as hex bytes (the first
+    /// 16 of them for longer data) and dates as RFC 3339 timestamps.
+    ///
+    /// ```text
+    /// {
+    ///   "name": "pig"
+    ///   "colors": [
+    ///     "pink"
+    ///   ]
+    ///   "bytes": <01 02 ff>
+    /// }
+    /// ```
+    pub fn to_tree(&self) -> String {
+        let mut tree = String::new();
+        render(self, None, 0, false, &mut tree);
+        tree
+    }
+
+    /// Renders the tree like [Value::to_tree], coloring the nodes with ANSI
+    /// escape codes by their type: keys are blue, strings green, numbers
+    /// yellow, data cyan and other scalars magenta.
+    ///
+    /// If the [NO_COLOR_VAR] environment variable is set to a non-empty
+    /// value, the plain tree is returned.
+    pub fn to_colored_tree(&self) -> String {
+        let colored = std::env::var_os(NO_COLOR_VAR).is_none_or(|var| var.is_empty());
+        let mut tree = String::new();
+        render(self, None, 0, colored, &mut tree);
+        tree
+    }
+}
+
+/// Appends the node to the tree, prefixed with its key inside of a dictionary.
+fn render(value: &Value, key: Option<&str>, depth: usize, colored: bool, tree: &mut String) {
+    let paint = |color: &str, text: &str| {
+        if colored {
+            format!("{color}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+    tree.push_str(&"  ".repeat(depth));
+    if let Some(key) = key {
+        tree.push_str(&paint(KEY_COLOR, &format!("{key:?}")));
+        tree.push_str(": ");
+    }
+    let line = match value {
+        Value::Dictionary(dict) => {
+            tree.push_str("{\n");
+            for (key, item) in dict {
+                render(&item, Some(&key), depth + 1, colored, tree);
+            }
+            format!("{}}}", "  ".repeat(depth))
+        }
+        Value::Array(array) => {
+            tree.push_str("[\n");
+            for item in array {
+                render(&item, None, depth + 1, colored, tree);
+            }
+            format!("{}]", "  ".repeat(depth))
+        }
+        Value::PString(string) => {
+            let text = String::from_utf8_lossy(string.as_bytes());
+            paint(STRING_COLOR, &format!("{text:?}"))
+        }
+        Value::Key(key) => paint(KEY_COLOR, &format!("{:?}", key.get())),
+        Value::Integer(integer) if integer.is_unsigned() => {
+            paint(NUMBER_COLOR, &integer.as_unsinged().to_string())
+        }
+        Value::Integer(integer) => paint(NUMBER_COLOR, &integer.as_singed().to_string()),
+        Value::Real(real) => paint(NUMBER_COLOR, &format!("{:?}", real.as_float())),
+        Value::Data(data) => {
+            let bytes = data.as_bytes();
+            let mut text = String::from("<");
+            for (i, byte) in bytes.iter().take(DATA_PREVIEW).enumerate() {
+                if i > 0 {
+                    text.push(' ');
+                }
+                write!(text, "{byte:02x}").unwrap();
+            }
+            if bytes.len() > DATA_PREVIEW {
+                write!(text, " ... {} bytes", bytes.len()).unwrap();
+            }
+            text.push('>');
+            paint(DATA_COLOR, &text)
+        }
+        Value::Boolean(boolean) => paint(OTHER_COLOR, &boolean.as_bool().to_string()),
+        Value::Date(date) => paint(OTHER_COLOR, &date.to_rfc3339()),
+        Value::Uid(uid) => paint(OTHER_COLOR, &format!("Uid({})", uid.get())),
+        Value::Null(_) => paint(OTHER_COLOR, "null"),
+    };
+    tree.push_str(&line);
+    tree.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn colored_tree() {
+        let plist: Value = dict!(
+            "name" => "pig",
+            "legs" => 4,
+            "height" => 0.9,
+            "pink" => true,
+            "colors" => array!("pink", array!()),
+            "bytes" => vec![1u8, 2, 255],
+            "ref" => Uid::new(3)
+        )
+        .into();
+        let plain = plist.to_tree();
+        assert_eq!(
+            plain,
+            r#"{
+  "name": "pig"
+  "legs": 4
+  "height": 0.9
+  "pink": true
+  "colors": [
+    "pink"
+    [
+    ]
+  ]
+  "bytes": <01 02 ff>
+  "ref": Uid(3)
+}
+"#
+        );
+
+        let mut colored = String::new();
+        super::render(&plist, None, 0, true, &mut colored);
+        assert!(colored.contains("\x1b[32m\"pig\"\x1b[0m"));
+        let mut stripped = String::new();
+        let mut rest = colored.as_str();
+        while let Some(start) = rest.find('\x1b') {
+            stripped.push_str(&rest[..start]);
+            rest = &rest[start + rest[start..].find('m').unwrap() + 1..];
+        }
+        stripped.push_str(rest);
+        assert_eq!(stripped, plain);
+    }
+
+    #[test]
+    fn tree_long_data() {
+        let plist = Value::from(vec![0u8; 20]);
+        assert_eq!(
+            plist.to_tree(),
+            "<00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 ... 20 bytes>\n"
+        );
+    }
+}
@@ -1,7 +1,7 @@
 pub mod array;
 mod boolean;
-mod data;
-mod date;
+pub(crate) mod data;
+pub(crate) mod date;
 pub mod dictionary;
 mod integer;
 mod key;
@@ -12,20 +12,26 @@ mod uid;
 
 pub use array::Array;
 pub use boolean::Boolean;
-pub use data::Data;
-pub use date::Date;
-pub use dictionary::Dictionary;
+pub use data::{BIG_INTEGER_TAG, Data, DataBuilder};
+pub use date::{Date, DateRange};
+pub use dictionary::{DictEntry, Dictionary, MergePolicy};
 pub use integer::Integer;
 pub use key::Key;
 pub use null::Null;
 pub use real::Real;
 pub use string::PString;
-pub use uid::Uid;
+pub use uid::{Uid, UidAllocator};
 
 use crate::{
     Error, PLIST_ERROR_SUCCESS, Value,
     unsafe_bindings::{self, plist_type},
 };
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ffi::c_char,
+    io::Write,
+};
 
 /// A common trait for any plist node.
 pub trait Node: crate::plist_ffi::PlistFFI {
@@ -135,46 +141,242 @@ pub trait Node: crate::plist_ffi::PlistFFI {
 
         Ok(openstep_string)
     }
+
+    /// Exports the plist node as an XML format directly into a writer.
+    ///
+    /// Unlike [Node::to_xml], the output isn't copied into a Rust string:
+    /// the buffer produced by `libplist` is written out and freed right away.
+    fn write_xml<W: Write>(&self, w: &mut W) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_xml(self.pointer(), out, len)
+        })
+    }
+
+    /// Exports the plist node as a JSON format directly into a writer.
+    ///
+    /// Set `prettify` to `true` to compose a prettified JSON string.
+    fn write_json<W: Write>(&self, w: &mut W, prettify: bool) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_json(self.pointer(), out, len, prettify as _)
+        })
+    }
+
+    /// Exports the plist node as a binary encoded plist directly into a writer.
+    fn write_bytes<W: Write>(&self, w: &mut W) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_bin(self.pointer(), out, len)
+        })
+    }
+
+    /// Exports the plist node to an OpenStep ASCII encoded plist
+    /// directly into a writer.
+    ///
+    /// Set `prettify` to `true` to compose a prettified string.
+    fn write_openstep<W: Write>(&self, w: &mut W, prettify: bool) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        write_exported(w, |out, len| unsafe {
+            unsafe_bindings::plist_to_openstep(self.pointer(), out, len, prettify as _)
+        })
+    }
+}
+
+/// Runs one of the `plist_to_*` functions and writes the buffer it allocates
+/// into `w`, freeing it afterwards. Write failures are reported as [Error::IO].
+pub(crate) fn write_exported(
+    w: &mut dyn Write,
+    export: impl FnOnce(*mut *mut c_char, *mut u32) -> unsafe_bindings::plist_err_t,
+) -> Result<(), Error> {
+    let mut buffer = std::ptr::null_mut();
+    let mut size = 0;
+    let result = export(&mut buffer, &mut size);
+    if result != PLIST_ERROR_SUCCESS {
+        return Err(result.into());
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(buffer as *const u8, size as usize) };
+    let written = w.write_all(slice);
+
+    // Free the allocated memory
+    unsafe { unsafe_bindings::plist_mem_free(buffer as *mut _) };
+
+    written.map_err(|_| Error::IO)
 }
 
 /// The type of a given plist
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum NodeType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    /// A boolean node, represented by [Boolean].
     Boolean,
+    /// An integer node, represented by [Integer].
     Integer,
+    /// A real node, represented by [Real].
     Real,
+    /// A date node, represented by [Date].
     Date,
+    /// A data node, represented by [Data].
     Data,
+    /// A string node, represented by [PString].
     String,
+    /// An array node, represented by [Array].
     Array,
+    /// A dictionary node, represented by [Dictionary].
     Dictionary,
+    /// A dictionary key node, represented by [Key].
     Key,
+    /// A uid node of keyed archives, represented by [Uid].
     Uid,
+    /// A null node, represented by [Null].
     Null,
 }
 
-impl From<plist_type> for NodeType {
+impl From<plist_type> for ValueType {
     fn from(i: plist_type) -> Self {
         match i {
             unsafe_bindings::plist_type_PLIST_NONE => {
                 panic!("`None` variant shoudn't happen. An invalid pointer has been passed.")
             }
-            unsafe_bindings::plist_type_PLIST_BOOLEAN => NodeType::Boolean,
-            unsafe_bindings::plist_type_PLIST_INT => NodeType::Integer,
-            unsafe_bindings::plist_type_PLIST_REAL => NodeType::Real,
-            unsafe_bindings::plist_type_PLIST_STRING => NodeType::String,
-            unsafe_bindings::plist_type_PLIST_ARRAY => NodeType::Array,
-            unsafe_bindings::plist_type_PLIST_DICT => NodeType::Dictionary,
-            unsafe_bindings::plist_type_PLIST_DATE => NodeType::Date,
-            unsafe_bindings::plist_type_PLIST_DATA => NodeType::Data,
-            unsafe_bindings::plist_type_PLIST_KEY => NodeType::Key,
-            unsafe_bindings::plist_type_PLIST_UID => NodeType::Uid,
-            unsafe_bindings::plist_type_PLIST_NULL => NodeType::Null,
+            unsafe_bindings::plist_type_PLIST_BOOLEAN => ValueType::Boolean,
+            unsafe_bindings::plist_type_PLIST_INT => ValueType::Integer,
+            unsafe_bindings::plist_type_PLIST_REAL => ValueType::Real,
+            unsafe_bindings::plist_type_PLIST_STRING => ValueType::String,
+            unsafe_bindings::plist_type_PLIST_ARRAY => ValueType::Array,
+            unsafe_bindings::plist_type_PLIST_DICT => ValueType::Dictionary,
+            unsafe_bindings::plist_type_PLIST_DATE => ValueType::Date,
+            unsafe_bindings::plist_type_PLIST_DATA => ValueType::Data,
+            unsafe_bindings::plist_type_PLIST_KEY => ValueType::Key,
+            unsafe_bindings::plist_type_PLIST_UID => ValueType::Uid,
+            unsafe_bindings::plist_type_PLIST_NULL => ValueType::Null,
             _ => panic!("Unknown plist type"),
         }
     }
 }
 
+/// A value or a fallible value accepted by the [try_dict!](crate::try_dict)
+/// and [try_array!](crate::try_array) macros.
+///
+/// It's implemented for anything convertible into a [Value] and for
+/// a [Result] of such a type with an error convertible into [Error].
+pub trait TryIntoValue<'a> {
+    /// Converts itself into a [Value] or returns the error.
+    fn try_into_value(self) -> Result<Value<'a>, Error>;
+}
+
+impl<'a, T: Into<Value<'a>>> TryIntoValue<'a> for T {
+    fn try_into_value(self) -> Result<Value<'a>, Error> {
+        Ok(self.into())
+    }
+}
+
+impl<'a, T: Into<Value<'a>>, E: Into<Error>> TryIntoValue<'a> for Result<T, E> {
+    fn try_into_value(self) -> Result<Value<'a>, Error> {
+        self.map(Into::into).map_err(Into::into)
+    }
+}
+
+/// Keeps the values handed out by the `Index` implementations of containers.
+///
+/// `Index` has to return a reference, but the children of a node are plain
+/// C pointers, so a [Value] wrapping each of them is created on demand and
+/// boxed here. The boxes are never moved or dropped while the container
+/// is borrowed, so the references stay valid for as long as the borrow.
+///
+/// A container only changes through a mutable borrow, which ends the
+/// borrows of the references handed out before. So values of nodes that
+/// are no longer children of the container can't be referenced anymore.
+/// They're dropped as soon as the cache holds as many values as the
+/// container has children, which keeps the cache no larger than the
+/// container even when it's changed through another wrapper of its node.
+#[derive(Default)]
+pub(crate) struct ChildCache {
+    values: RefCell<HashMap<unsafe_bindings::plist_t, Box<Value<'static>>>>,
+}
+
+impl ChildCache {
+    /// Returns a reference to a borrowed value of the given child node
+    /// of the `parent` container.
+    pub(crate) fn get<'v>(
+        &self,
+        parent: unsafe_bindings::plist_t,
+        pointer: unsafe_bindings::plist_t,
+    ) -> &Value<'v> {
+        let typ: ValueType = unsafe { unsafe_bindings::plist_get_node_type(pointer) }.into();
+        let mut values = self.values.borrow_mut();
+        if let Some(cached) = values.get(&pointer)
+            && cached.value_type() == typ
+        {
+            let cached: *const Value<'static> = &**cached;
+            // The box is kept alive and in place while the container is borrowed
+            return unsafe { &*cached };
+        }
+        let len = match ValueType::from(unsafe { unsafe_bindings::plist_get_node_type(parent) }) {
+            ValueType::Array => unsafe { unsafe_bindings::plist_array_get_size(parent) },
+            _ => unsafe { unsafe_bindings::plist_dict_get_size(parent) },
+        };
+        if values.len() >= len as usize {
+            let children: HashSet<_> = crate::iter::children(parent).into_iter().collect();
+            values.retain(|child, _| children.contains(child));
+        }
+        let mut value = unsafe { crate::from_pointer(pointer) };
+        value.as_node_mut().set_false_drop(true);
+        let boxed = Box::new(value);
+        let cached: *const Value<'static> = &*boxed;
+        // A value cached for a reused pointer belongs to a removed node
+        values.insert(pointer, boxed);
+        unsafe { &*cached }
+    }
+
+    /// Drops every cached value.
+    ///
+    /// Containers call it from their `&mut self` methods, since a mutable
+    /// borrow means that no reference handed out by `Index` is alive anymore.
+    pub(crate) fn clear(&mut self) {
+        self.values.get_mut().clear();
+    }
+
+    /// Returns the number of cached values.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.values.borrow().len()
+    }
+}
+
+impl std::fmt::Debug for ChildCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ChildCache")
+    }
+}
+
+/// Returns the value to write back in place of the child node at `pointer`
+/// after a callback got a mutable reference to its wrapper, or [None]
+/// if the node was only changed in place.
+///
+/// A callback can assign a whole new value, which then has to replace the
+/// child. If the new value is itself a borrowed node of some tree, it's
+/// copied, since containers take ownership of the values they store.
+pub(crate) fn replaced<'b>(
+    value: Value<'b>,
+    pointer: unsafe_bindings::plist_t,
+) -> Option<Value<'b>> {
+    if value.as_node().pointer() == pointer {
+        None
+    } else if value.as_node().false_drop() {
+        Some(value.clone())
+    } else {
+        Some(value)
+    }
+}
+
 // The main reason of introducing a separate struct to contain a value is
 // that returning a plain Value results in breaking Rust ownership rules. For instance,
 // you can have an *immutable* Array and get a Value: now you do *whatever* you want with it.
@@ -185,7 +387,7 @@ impl From<plist_type> for NodeType {
 ///
 /// It automatically dereferences to the underlying [Value].
 #[derive(Debug, PartialEq)]
-pub struct Item<'a>(Value<'a>);
+pub struct Item<'a>(pub(crate) Value<'a>);
 
 impl<'a> std::ops::Deref for Item<'a> {
     type Target = Value<'a>;
@@ -216,7 +418,7 @@ impl<'a> std::ops::Deref for Item<'a> {
 /// assert_eq!(updated_value.as_boolean(), new_value.as_boolean())
 /// ```
 #[derive(Debug, PartialEq)]
-pub struct ItemMut<'a>(Value<'a>);
+pub struct ItemMut<'a>(pub(crate) Value<'a>);
 
 impl<'a> std::ops::Deref for ItemMut<'a> {
     type Target = Value<'a>;
@@ -236,7 +438,7 @@ impl std::ops::DerefMut for ItemMut<'_> {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_node {
-    ($(#[$outer:meta])* $name:ident) => {
+    ($(#[$outer:meta])* $name:ident $(, $field:ident: $type:ty)*) => {
         $(#[$outer])*
         #[cfg_attr(not(feature = "clean_debug"), derive(Debug))]
         pub struct $name<'a> {
@@ -244,6 +446,7 @@ macro_rules! impl_node {
             pub(crate) false_drop: bool,
             // Used if the current plist is a child
             pub(crate) phantom: std::marker::PhantomData<&'a $crate::$name<'a>>,
+            $(pub(crate) $field: $type,)*
         }
 
         impl $crate::plist_ffi::PlistFFI for $name<'_> {
@@ -268,6 +471,18 @@ macro_rules! impl_node {
             }
         }
 
+        /// Returns [Error::Format](crate::Error::Format) if the value is of another type.
+        impl<'a> TryFrom<$crate::Value<'a>> for $name<'a> {
+            type Error = $crate::Error;
+
+            fn try_from(value: $crate::Value<'a>) -> Result<Self, Self::Error> {
+                match value {
+                    $crate::Value::$name(value) => Ok(value),
+                    _ => Err($crate::Error::Format),
+                }
+            }
+        }
+
         impl Drop for $name<'_> {
             fn drop(&mut self) {
                 use $crate::plist_ffi::PlistFFI;
@@ -1,10 +1,12 @@
-use super::{Item, ItemMut};
+use super::{Data, DateRange, Item, ItemMut};
 use crate::{Value, Node, unsafe_bindings};
 use core::ffi::c_void;
+use std::cmp::Ordering;
 
 crate::impl_node!(
     /// An array plist node.
-    Array
+    Array,
+    children: super::ChildCache
 );
 
 impl<'a> Array<'a> {
@@ -14,9 +16,23 @@ impl<'a> Array<'a> {
             pointer: unsafe { unsafe_bindings::plist_new_array() },
             false_drop: false,
             phantom: std::marker::PhantomData,
+            children: Default::default(),
         }
     }
 
+    /// Creates an array of `count` deep copies of the value,
+    /// like `vec![value; count]`.
+    ///
+    /// Every element is an independent copy, so changing one of them
+    /// leaves the others untouched.
+    pub fn repeat(value: &Value, count: u32) -> Self {
+        let mut array = Self::new();
+        for _ in 0..count {
+            array.append(value.clone());
+        }
+        array
+    }
+
     /// Returns the number of elements in the array.
     pub fn len(&self) -> u32 {
         unsafe { unsafe_bindings::plist_array_get_size(self.pointer) }
@@ -47,15 +63,47 @@ impl<'a> Array<'a> {
     /// Returns a mutable reference to the value corresponding to the index
     /// or [None] if out of bounds.
     pub fn get_mut(&mut self, index: u32) -> Option<ItemMut<'_>> {
+        self.children.clear();
         self.internal_get(index).map(ItemMut)
     }
 
+    /// Returns an immutable reference to the first element,
+    /// or [None] if the array is empty.
+    pub fn first(&self) -> Option<Item<'_>> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the first element,
+    /// or [None] if the array is empty.
+    pub fn first_mut(&mut self) -> Option<ItemMut<'_>> {
+        self.get_mut(0)
+    }
+
+    /// Returns an immutable reference to the last element,
+    /// or [None] if the array is empty.
+    pub fn last(&self) -> Option<Item<'_>> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    /// Returns a mutable reference to the last element,
+    /// or [None] if the array is empty.
+    pub fn last_mut(&mut self) -> Option<ItemMut<'_>> {
+        self.get_mut(self.len().checked_sub(1)?)
+    }
+
+    /// Returns the first element and an iterator over the rest of them,
+    /// or [None] if the array is empty.
+    pub fn split_first(&self) -> Option<(Item<'_>, std::iter::Skip<Iter<'_, 'a>>)> {
+        Some((self.first()?, self.iter().skip(1)))
+    }
+
     /// Sets the value of the index to the given value.
     ///
     /// The previous element of the same index is discarded.
     /// # Panics
     /// Panics if the index is out of bounds.
     pub fn set<'b>(&mut self, value: impl Into<Value<'b>>, index: u32) {
+        self.children.clear();
         let len = self.len();
         if index >= len {
             panic!("inserting index (is {index}) should be < len (is {len})");
@@ -69,6 +117,7 @@ impl<'a> Array<'a> {
 
     /// Appends a new item at the end of the array.
     pub fn append<'b>(&mut self, value: impl Into<Value<'b>>) {
+        self.children.clear();
         let mut value = value.into();
         value.as_node_mut().set_false_drop(true);
         unsafe {
@@ -76,11 +125,57 @@ impl<'a> Array<'a> {
         };
     }
 
+    /// Appends deep copies of every item of `other` to the end of the array.
+    ///
+    /// `other` is left untouched and can still be used afterwards.
+    pub fn extend_from(&mut self, other: &Array) {
+        for item in other {
+            self.append(item.clone());
+        }
+    }
+
+    /// Appends every value of an iterator to the end of the array,
+    /// like [Array::append] does for a single one.
+    ///
+    /// Unlike [Extend::extend], the items can be of any type that converts
+    /// into a [Value], e.g. `array.append_all(["a", "b"])`.
+    pub fn append_all<'b, I>(&mut self, values: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<Value<'b>>,
+    {
+        for value in values {
+            self.append(value);
+        }
+    }
+
+    /// Inserts an element at the beginning of the array, shifting all elements to the right.
+    pub fn push_front<'b>(&mut self, value: impl Into<Value<'b>>) {
+        if self.is_empty() {
+            self.append(value);
+        } else {
+            self.insert(value, 0);
+        }
+    }
+
+    /// Removes the last element from the array and returns it,
+    /// or [None] if it's empty.
+    ///
+    /// The element is copied before being removed from the array,
+    /// so it gets a lifetime of a caller.
+    pub fn pop<'b>(&mut self) -> Option<Value<'b>> {
+        let index = self.len().checked_sub(1)?;
+        let value = self.get(index)?.clone();
+        self.remove(index);
+        Some(value)
+    }
+
     /// Inserts an element at position index, shifting all elements after it to the right.
     ///
     /// # Panics
     /// Panics if the index is out of bounds.
     pub fn insert<'b>(&mut self, value: impl Into<Value<'b>>, index: u32) {
+        self.children.clear();
         let len = self.len();
         if index >= len {
             panic!("inserting index (is {index}) should be < len (is {len})");
@@ -97,6 +192,7 @@ impl<'a> Array<'a> {
     /// # Panics
     /// Panics if the index is out of bounds.
     pub fn remove(&mut self, index: u32) {
+        self.children.clear();
         let len = self.len();
         if index >= len {
             panic!("removal index (is {index}) should be < len (is {len})");
@@ -104,6 +200,153 @@ impl<'a> Array<'a> {
         unsafe { unsafe_bindings::plist_array_remove_item(self.pointer, index) };
     }
 
+    /// Retains only the elements for which the predicate returns `true`.
+    ///
+    /// The predicate sees every element exactly once, from front to back.
+    /// Rejected elements are removed afterwards, starting from the end so
+    /// the remaining indices stay valid.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Value) -> bool,
+    {
+        self.children.clear();
+        let rejected: Vec<u32> = (0..self.len())
+            .filter(|&index| !self.internal_get(index).is_some_and(|value| f(&value)))
+            .collect();
+        for index in rejected.into_iter().rev() {
+            unsafe { unsafe_bindings::plist_array_remove_item(self.pointer, index) };
+        }
+    }
+
+    /// Retains only the elements for which the predicate returns `true`,
+    /// passing every element mutably so the kept ones can be updated.
+    ///
+    /// Changes made through the reference are written to the array, including
+    /// assigning a whole new value. Rejected elements are removed afterwards,
+    /// starting from the end so the remaining indices stay valid.
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut Value) -> bool,
+    {
+        self.children.clear();
+        let mut rejected = Vec::new();
+        for index in 0..self.len() {
+            let pointer = unsafe { unsafe_bindings::plist_array_get_item(self.pointer, index) };
+            let mut value = unsafe { crate::from_pointer(pointer) };
+            value.as_node_mut().set_false_drop(true);
+            if !f(&mut value) {
+                rejected.push(index);
+            }
+            if let Some(value) = super::replaced(value, pointer) {
+                self.set(value, index);
+            }
+        }
+        for index in rejected.into_iter().rev() {
+            unsafe { unsafe_bindings::plist_array_remove_item(self.pointer, index) };
+        }
+    }
+
+    /// Rotates the array in-place such that the first `mid` elements move
+    /// to the end while the rest move to the front.
+    ///
+    /// `mid` is taken modulo the length of the array. Every value is copied
+    /// and written back in the rotated order.
+    pub fn rotate_left(&mut self, mid: u32) {
+        let len = self.len();
+        if len == 0 {
+            return;
+        }
+        let mut values = self.to_vec();
+        values.rotate_left((mid % len) as usize);
+        self.rewrite(values);
+    }
+
+    /// Rotates the array in-place such that the last `k` elements move
+    /// to the front while the rest move to the end.
+    ///
+    /// `k` is taken modulo the length of the array. Every value is copied
+    /// and written back in the rotated order.
+    pub fn rotate_right(&mut self, k: u32) {
+        let len = self.len();
+        if len == 0 {
+            return;
+        }
+        let mut values = self.to_vec();
+        values.rotate_right((k % len) as usize);
+        self.rewrite(values);
+    }
+
+    /// Sorts the array in-place with a comparator function.
+    ///
+    /// The sort is stable. The comparator gets the items in place, and once
+    /// the order is known only the items that change their position are
+    /// copied and written back. Empty and single-element arrays are left untouched.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        let len = self.len();
+        if len <= 1 {
+            return;
+        }
+        let items: Vec<Value> = (0..len).filter_map(|i| self.internal_get(i)).collect();
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by(|&a, &b| compare(&items[a], &items[b]));
+        let moved: Vec<(u32, Value<'static>)> = order
+            .iter()
+            .enumerate()
+            .filter(|(index, from)| index != *from)
+            .map(|(index, &from)| (index as u32, items[from].clone()))
+            .collect();
+        drop(items);
+        for (index, value) in moved {
+            self.set(value, index);
+        }
+    }
+
+    /// Sorts the array in-place with a key extraction function.
+    ///
+    /// See [Array::sort_by] for the details.
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Value) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Overwrites the array items with the given values one by one.
+    fn rewrite(&mut self, values: Vec<Value<'_>>) {
+        for (index, value) in values.into_iter().enumerate() {
+            self.set(value, index as u32);
+        }
+    }
+
+    /// Returns a new array with copies of the dictionaries whose `key` field
+    /// is a date inside the `range`.
+    ///
+    /// Items that aren't dictionaries or don't have a date under `key`
+    /// are skipped.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the supplied key contains an internal 0 byte.
+    pub fn filter_by_date_range<'b>(&self, key: &str, range: &DateRange) -> Array<'b> {
+        let mut filtered = Array::new();
+        for item in self {
+            let Some(dict) = item.as_dictionary() else {
+                continue;
+            };
+            let in_range = dict
+                .get(key)
+                .is_some_and(|field| field.as_date().is_some_and(|date| range.contains(date)));
+            if in_range {
+                filtered.append(item.clone());
+            }
+        }
+        filtered
+    }
+
     /// Creates an immutable iterator over the array.
     pub fn iter(&self) -> Iter<'_, 'a> {
         self.into_iter()
@@ -129,6 +372,84 @@ impl<'a> Array<'a> {
         values
     }
 
+    /// Consumes the array and returns an iterator over owned sub-arrays of
+    /// at most `batch_size` items each.
+    ///
+    /// The items are copied lazily, one batch at a time. The last batch may
+    /// be shorter.
+    ///
+    /// # Panics
+    /// Panics if `batch_size` is 0.
+    pub fn into_batches<'b>(self, batch_size: u32) -> impl Iterator<Item = Array<'b>> {
+        assert!(batch_size != 0, "batch size must be non-zero");
+        let mut start = 0;
+        std::iter::from_fn(move || {
+            let len = self.len();
+            if start >= len {
+                return None;
+            }
+            let end = start.saturating_add(batch_size).min(len);
+            let mut batch = Array::new();
+            for index in start..end {
+                batch.append(self.get(index)?.clone());
+            }
+            start = end;
+            Some(batch)
+        })
+    }
+
+    /// Returns the key-value pairs of an array of single-entry dictionaries,
+    /// preserving the array order, by copying them.
+    ///
+    /// This is how some plists encode ordered maps. Returns [None] if any of
+    /// the items isn't a dictionary with exactly one entry.
+    pub fn to_ordered_pairs<'b>(&self) -> Option<Vec<(String, Value<'b>)>> {
+        let mut pairs = Vec::with_capacity(self.len() as usize);
+        for item in self {
+            let dict = item.as_dictionary()?;
+            if dict.len() != 1 {
+                return None;
+            }
+            let (key, value) = dict.iter().next()?;
+            pairs.push((key, value.clone()));
+        }
+        Some(pairs)
+    }
+
+    /// Concatenates the strings of the array, placing the separator
+    /// between each of them.
+    ///
+    /// Returns [None] if any of the items isn't a valid UTF-8 string.
+    /// An empty array gives an empty string.
+    pub fn join_strings(&self, separator: &str) -> Option<String> {
+        let mut joined = String::new();
+        for (index, item) in self.iter().enumerate() {
+            if index > 0 {
+                joined.push_str(separator);
+            }
+            joined.push_str(item.as_string()?.try_as_str().ok()?);
+        }
+        Some(joined)
+    }
+
+    /// Packs an array of integers in the `0..=255` range into a data node,
+    /// one byte per element.
+    ///
+    /// Some plists store binary blobs this way instead of using data nodes.
+    /// Returns [None] if any of the items isn't an integer or is out of range.
+    /// An empty array gives an empty data node.
+    pub fn try_into_data<'b>(&self) -> Option<Data<'b>> {
+        let mut bytes = Vec::with_capacity(self.len() as usize);
+        for item in self {
+            let integer = item.as_integer()?;
+            if integer.is_negative() {
+                return None;
+            }
+            bytes.push(u8::try_from(integer.as_unsinged()).ok()?);
+        }
+        Some(Data::new(&bytes))
+    }
+
     #[allow(clippy::should_implement_trait)]
     /// Clones the value and gives it a lifetime of a caller.
     pub fn clone<'b>(&self) -> Array<'b> {
@@ -162,6 +483,47 @@ macro_rules! array {
     };
 }
 
+/// A fallible version of [array!].
+///
+/// Every value can be either anything convertible into a [Value] or
+/// a [Result] of it (see [TryIntoValue](crate::TryIntoValue)). The first error
+/// is returned and the rest of the values aren't evaluated.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{PString, array, try_array};
+///
+/// let arr = try_array!(PString::try_new("Hello World"), 123).unwrap();
+/// assert_eq!(arr, array!("Hello World", 123));
+/// assert!(try_array!(PString::try_new("Hello\0World")).is_err());
+/// ```
+#[macro_export]
+macro_rules! try_array {
+    () => {::std::result::Result::<$crate::Array, $crate::Error>::Ok($crate::Array::new())};
+    (
+        $($val:expr),+ $(,)?
+    ) => {
+        'build: {
+            let mut array = $crate::Array::new();
+            $(
+                match $crate::TryIntoValue::try_into_value($val) {
+                    ::std::result::Result::Ok(value) => array.append(value),
+                    ::std::result::Result::Err(error) => {
+                        break 'build ::std::result::Result::Err(error);
+                    }
+                }
+            )+
+            ::std::result::Result::<$crate::Array, $crate::Error>::Ok(array)
+        }
+    };
+}
+
+impl<'b> Extend<Value<'b>> for Array<'_> {
+    fn extend<I: IntoIterator<Item = Value<'b>>>(&mut self, iter: I) {
+        self.append_all(iter);
+    }
+}
+
 impl<'a> From<Vec<Value<'a>>> for Array<'_> {
     fn from(vec: Vec<Value<'a>>) -> Self {
         let mut array = Self::new();
@@ -172,6 +534,35 @@ impl<'a> From<Vec<Value<'a>>> for Array<'_> {
     }
 }
 
+impl<'b, V> FromIterator<V> for Array<'_>
+where
+    V: Into<Value<'b>>,
+{
+    fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
+        let mut array = Self::new();
+        array.append_all(iter);
+        array
+    }
+}
+
+/// Indexing returns a reference that lives as long as the array is borrowed.
+///
+/// # Panics
+/// Panics if the index is out of bounds. Use [Array::get] for a non-panicking
+/// lookup.
+impl<'a> std::ops::Index<u32> for Array<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, index: u32) -> &Self::Output {
+        let len = self.len();
+        if index >= len {
+            panic!("index out of bounds: the len is {len} but the index is {index}");
+        }
+        let item = unsafe { unsafe_bindings::plist_array_get_item(self.pointer, index) };
+        self.children.get(self.pointer, item)
+    }
+}
+
 impl Default for Array<'_> {
     fn default() -> Self {
         Self::new()
@@ -236,6 +627,7 @@ impl<'a, 'b> IntoIterator for &'a mut Array<'b> {
     type IntoIter = IterMut<'a, 'b>;
 
     fn into_iter(self) -> Self::IntoIter {
+        self.children.clear();
         let mut iter_pointer = unsafe { std::mem::zeroed() };
         unsafe { unsafe_bindings::plist_array_new_iter(self.pointer(), &mut iter_pointer) }
         IterMut {
@@ -245,6 +637,45 @@ impl<'a, 'b> IntoIterator for &'a mut Array<'b> {
     }
 }
 
+/// An owning array iterator, created by calling `into_iter` on an [Array].
+///
+/// `libplist` can't detach children from their parent, so every element
+/// is yielded as a deep copy, costing an allocation per node of its
+/// subtree. The array itself is freed when the iterator is dropped.
+/// Iterate over `&array` to avoid the copies.
+#[derive(Debug)]
+pub struct IntoIter<'a> {
+    array: Array<'a>,
+    index: u32,
+}
+
+impl<'a> IntoIterator for Array<'a> {
+    type Item = Value<'a>;
+    type IntoIter = IntoIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            array: self,
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for IntoIter<'a> {
+    type Item = Value<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pointer = self.array.get(self.index)?.pointer();
+        self.index += 1;
+        Some(unsafe { crate::from_pointer(unsafe_bindings::plist_copy(pointer)) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.array.len() - self.index) as usize;
+        (len, Some(len))
+    }
+}
+
 fn iter_next<'a>(array: &Array<'a>, iter_pointer: *mut c_void) -> Option<Value<'a>> {
     let mut to_fill = unsafe { std::mem::zeroed() };
     // Getting next item in array
@@ -295,10 +726,23 @@ impl Drop for IterMut<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Array, Boolean, PString, Value};
+    use crate::{Array, Boolean, Integer, PString, Value, dict};
 
     const ARRAY: [u64; 4] = [0, 1, 2, 3];
 
+    #[test]
+    fn array_index_cache_is_cleared() {
+        let mut array = array!(0);
+        for i in 0..100 {
+            assert_eq!(array[0], Value::from(i));
+            array.set(i + 1, 0);
+            array.append("x");
+            assert_eq!(array[1], Value::from("x"));
+            array.remove(1);
+        }
+        assert!(array.children.len() <= 1);
+    }
+
     #[test]
     fn array_append() {
         let boolean = Boolean::new(true);
@@ -372,6 +816,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn array_retain_mut() {
+        let mut plist: Array = (1..=6).collect();
+        plist.retain_mut(|value| {
+            let integer = value.as_integer_mut().unwrap();
+            let x = integer.as_singed();
+            integer.set_signed(x * 2);
+            x % 2 == 0
+        });
+        assert_eq!(plist, (1..=3).map(|x| x * 4).collect());
+
+        let mut plist = array!(1, "two", 3);
+        plist.retain_mut(|value| match value.as_string() {
+            Some(_) => {
+                *value = Boolean::new(true).into();
+                true
+            }
+            None => false,
+        });
+        assert_eq!(plist, array!(true));
+    }
+
+    #[test]
+    fn array_repeat() {
+        let template: Value = dict!("name" => "pig", "legs" => 4).into();
+        let mut plist = Array::repeat(&template, 5);
+        assert_eq!(plist.len(), 5);
+        plist
+            .get_mut(2)
+            .unwrap()
+            .as_dictionary_mut()
+            .unwrap()
+            .insert("name", "lamb");
+        for (i, item) in plist.iter().enumerate() {
+            let name = if i == 2 { "lamb" } else { "pig" };
+            assert_eq!(*item, dict!("name" => name, "legs" => 4).into());
+        }
+        assert_eq!(template, dict!("name" => "pig", "legs" => 4).into());
+        assert!(Array::repeat(&template, 0).is_empty());
+    }
+
+    #[test]
+    fn array_into_iter() {
+        let plist: Array = ARRAY.into_iter().collect();
+        let iter = plist.into_iter();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        let values: Vec<Value> = iter.collect();
+        assert_eq!(values.len(), 4);
+        for (x, value) in ARRAY.into_iter().zip(values) {
+            assert_eq!(x, value.as_integer().unwrap().as_unsinged());
+        }
+
+        let nested = array!(dict!("a" => 1), "b");
+        let mut iter = nested.into_iter();
+        let first = iter.next().unwrap();
+        drop(iter);
+        assert_eq!(first, Value::from(dict!("a" => 1)));
+    }
+
     #[test]
     fn array_iter_mut() {
         // Create a new plist array [9, 9, 9, 9]
@@ -400,6 +903,247 @@ mod tests {
         }
     }
 
+    #[test]
+    fn array_pop_push_front() {
+        let mut arr = Array::new();
+        assert!(arr.pop().is_none());
+        arr.push_front(2);
+        arr.push_front(1);
+        arr.append(3);
+        assert_eq!(arr, array!(1, 2, 3));
+
+        let last = arr.pop().unwrap();
+        assert_eq!(last, Value::from(3));
+        assert_eq!(arr, array!(1, 2));
+        std::mem::drop(arr);
+        // The popped value is independent of the array
+        assert_eq!(last.as_integer().unwrap().as_unsinged(), 3);
+    }
+
+    #[test]
+    fn array_filter_by_date_range() {
+        use crate::{DateRange, dict};
+        use std::time::Duration;
+
+        let records = array!(
+            dict!("id" => 1, "timestamp" => Duration::from_secs(1000)),
+            dict!("id" => 2, "timestamp" => Duration::from_secs(2000)),
+            dict!("id" => 3, "timestamp" => Duration::from_secs(3000)),
+            dict!("id" => 4),
+            "not a record"
+        );
+        let range = DateRange::from(Duration::from_secs(1500)..Duration::from_secs(3000));
+        let filtered = records.filter_by_date_range("timestamp", &range);
+        assert_eq!(
+            filtered,
+            array!(dict!("id" => 2, "timestamp" => Duration::from_secs(2000)))
+        );
+    }
+
+    #[test]
+    fn array_extend_from() {
+        let mut arr = array!(1, 2);
+        let other = array!(3, array!(4));
+        arr.extend_from(&other);
+        assert_eq!(arr, array!(1, 2, 3, array!(4)));
+
+        // The copies are independent of the source array
+        arr.get_mut(2).unwrap().replace_with(&Value::from(30));
+        std::mem::drop(arr);
+        assert_eq!(other, array!(3, array!(4)));
+        assert_eq!(other.len(), 2);
+    }
+
+    #[test]
+    fn array_to_ordered_pairs() {
+        use crate::dict;
+
+        let arr = array!(
+            dict!("zebra" => 1),
+            dict!("apple" => "two"),
+            dict!("mango" => 3)
+        );
+        let pairs = arr.to_ordered_pairs().unwrap();
+        let keys: Vec<&str> = pairs.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["zebra", "apple", "mango"]);
+        assert_eq!(pairs[1].1, Value::from("two"));
+
+        assert!(
+            array!(dict!("a" => 1, "b" => 2))
+                .to_ordered_pairs()
+                .is_none()
+        );
+        assert!(array!(dict!("a" => 1), 2).to_ordered_pairs().is_none());
+        assert_eq!(Array::new().to_ordered_pairs(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn array_into_batches() {
+        let arr = array!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9);
+        let batches: Vec<Array> = arr.into_batches(3).collect();
+        assert_eq!(batches.len(), 4);
+        assert_eq!(batches[0], array!(0, 1, 2));
+        assert_eq!(batches[2], array!(6, 7, 8));
+        assert_eq!(batches[3], array!(9));
+
+        assert_eq!(Array::new().into_batches(3).count(), 0);
+    }
+
+    #[test]
+    fn array_rotate() {
+        let mut arr = array!(0, 1, 2, 3, 4);
+        arr.rotate_left(2);
+        assert_eq!(arr, array!(2, 3, 4, 0, 1));
+        arr.rotate_right(7);
+        assert_eq!(arr, array!(0, 1, 2, 3, 4));
+        arr.rotate_right(1);
+        assert_eq!(arr, array!(4, 0, 1, 2, 3));
+    }
+
+    #[test]
+    fn array_retain() {
+        let mut array = array!(1, 2, 3, 4, 5, 6);
+        let mut seen = Vec::new();
+        array.retain(|value| {
+            let n = value.as_integer().unwrap().as_singed();
+            seen.push(n);
+            n % 2 == 0
+        });
+        assert_eq!(seen, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(array, array!(2, 4, 6));
+
+        array.retain(|_| false);
+        assert!(array.is_empty());
+        array.retain(|_| unreachable!());
+    }
+
+    #[test]
+    fn array_try_into_data() {
+        let data = array!(0, 255, 128).try_into_data().unwrap();
+        assert_eq!(data.as_bytes(), [0, 255, 128]);
+        assert!(array!().try_into_data().unwrap().is_empty());
+        assert_eq!(array!(1, 256).try_into_data(), None);
+        assert_eq!(array!(-1).try_into_data(), None);
+        assert_eq!(array!(1, "2").try_into_data(), None);
+    }
+
+    #[test]
+    fn array_extend() {
+        let mut array = array!(1);
+        array.extend(vec![Value::from(2), Value::from("three")]);
+        array.extend((4..6).map(Value::from));
+        array.append_all(["six", "seven"]);
+        array.append_all(std::iter::empty::<bool>());
+        assert_eq!(array, array!(1, 2, "three", 4, 5, "six", "seven"));
+    }
+
+    #[test]
+    fn array_join_strings() {
+        assert_eq!(array!("a", "b", "c").join_strings(", ").unwrap(), "a, b, c");
+        assert_eq!(array!().join_strings("-").unwrap(), "");
+        assert_eq!(array!("a", 1).join_strings("-"), None);
+    }
+
+    #[test]
+    fn array_from_iter() {
+        let array: Array = (0..3).map(Integer::from).collect();
+        assert_eq!(array, array!(0, 1, 2));
+        let array: Array = ["a", "b"].into_iter().collect();
+        assert_eq!(array, array!("a", "b"));
+        assert!(std::iter::empty::<Value>().collect::<Array>().is_empty());
+    }
+
+    #[test]
+    fn array_first_last() {
+        let mut array = array!(1, 2, 3);
+        assert_eq!(*array.first().unwrap(), Value::from(1));
+        assert_eq!(*array.last().unwrap(), Value::from(3));
+        array
+            .last_mut()
+            .unwrap()
+            .as_integer_mut()
+            .unwrap()
+            .set_signed(30);
+        array
+            .first_mut()
+            .unwrap()
+            .as_integer_mut()
+            .unwrap()
+            .set_signed(10);
+        assert_eq!(array, array!(10, 2, 30));
+
+        let (first, rest) = array.split_first().unwrap();
+        assert_eq!(*first, Value::from(10));
+        assert_eq!(
+            rest.map(|item| item.clone()).collect::<Array>(),
+            array!(2, 30)
+        );
+
+        let mut empty = array!();
+        assert!(empty.first().is_none());
+        assert!(empty.last().is_none());
+        assert!(empty.last_mut().is_none());
+        assert!(empty.split_first().is_none());
+    }
+
+    #[test]
+    fn array_sort_by() {
+        let mut arr = array!(3, 1, 2);
+        arr.sort_by(|a, b| {
+            let a = a.as_integer().unwrap().as_singed();
+            a.cmp(&b.as_integer().unwrap().as_singed())
+        });
+        assert_eq!(arr, array!(1, 2, 3));
+
+        let mut arr = array!(
+            dict!("Name" => "pig", "Legs" => 4),
+            dict!("Name" => "chicken", "Legs" => 2),
+            dict!("Name" => "cow", "Legs" => 4)
+        );
+        arr.sort_by_key(|item| {
+            let legs = item.as_dictionary().unwrap().get("Legs").unwrap();
+            legs.as_integer().unwrap().as_unsinged()
+        });
+        let names: Vec<String> = arr
+            .iter()
+            .map(|item| {
+                let name = item.as_dictionary().unwrap().get("Name").unwrap();
+                name.as_string().unwrap().as_str().to_string()
+            })
+            .collect();
+        assert_eq!(names, ["chicken", "pig", "cow"]);
+
+        let mut single = array!("only");
+        single.sort_by(|_, _| unreachable!());
+        assert_eq!(single, array!("only"));
+        Array::new().sort_by(|_, _| unreachable!());
+    }
+
+    #[test]
+    fn array_try_array() {
+        let arr = try_array!(PString::try_new("pig"), 4, PString::try_new("lamb")).unwrap();
+        assert_eq!(arr, array!("pig", 4, "lamb"));
+        let result = try_array!(1, PString::try_new("wo\0rm"));
+        assert_eq!(result.unwrap_err(), crate::Error::InvalidArg);
+    }
+
+    #[test]
+    fn array_index() {
+        let arr = array!("pig", array!(1, 2));
+        assert_eq!(arr[0].as_string().unwrap().as_str(), "pig");
+        assert_eq!(arr[1].as_array().unwrap()[1], Value::from(2));
+        let first = &arr[0];
+        let again = &arr[0];
+        assert!(std::ptr::eq(first, again));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn array_index_out_of_bounds() {
+        let arr = array!(1);
+        let _ = &arr[1];
+    }
+
     #[test]
     fn replace_with() {
         let mut a: Value = Boolean::new(true).into();
@@ -89,8 +89,8 @@ mod tests {
     #[test]
     fn bool() {
         let mut p = Boolean::new(false);
-        assert_eq!(p.as_bool(), false);
+        assert!(!p.as_bool());
         p.set(true);
-        assert_eq!(p.as_bool(), true);
+        assert!(p.as_bool());
     }
 }
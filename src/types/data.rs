@@ -1,4 +1,4 @@
-use crate::{Value, unsafe_bindings};
+use crate::{Error, Value, unsafe_bindings};
 use core::ffi::c_char;
 
 crate::impl_node!(
@@ -6,6 +6,10 @@ crate::impl_node!(
     Data
 );
 
+/// The prefix of data nodes holding integers that don't fit into 64 bits,
+/// see [Value::big_integer].
+pub const BIG_INTEGER_TAG: &[u8] = b"BIGINT:";
+
 impl Data<'_> {
     /// Creates a new data plist node from a slice of bytes.
     pub fn new(data: &[u8]) -> Self {
@@ -19,6 +23,22 @@ impl Data<'_> {
         }
     }
 
+    /// Creates a new data plist node from a base64 string.
+    ///
+    /// The standard alphabet is expected, the padding is optional and ASCII
+    /// whitespace is ignored, so the contents of XML `<data>` elements are
+    /// accepted as is. Returns [Error::Parse] if the string is malformed.
+    pub fn from_base64(encoded: &str) -> Result<Self, Error> {
+        let bytes = decode_base64(encoded).ok_or(Error::Parse)?;
+        Ok(Self::new(&bytes))
+    }
+
+    /// Returns the data encoded as a base64 string with the standard
+    /// alphabet and padding.
+    pub fn to_base64(&self) -> String {
+        encode_base64(self.as_bytes())
+    }
+
     /// Returns a byte slice of the data value.
     pub fn as_bytes(&self) -> &'_ [u8] {
         let mut size = 0;
@@ -31,6 +51,16 @@ impl Data<'_> {
         self.as_bytes().to_vec()
     }
 
+    /// Writes the bytes to a writer without copying them first and
+    /// returns the number of written bytes.
+    ///
+    /// Returns [Error::IO] if writing fails.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> Result<u64, Error> {
+        let bytes = self.as_bytes();
+        writer.write_all(bytes).map_err(|_| Error::IO)?;
+        Ok(bytes.len() as u64)
+    }
+
     /// Returns the length of a contained bytes array.
     pub fn len(&self) -> u64 {
         self.as_bytes().len() as u64
@@ -63,6 +93,60 @@ impl Data<'_> {
     }
 }
 
+/// Builds a [Data] node from chunks of bytes written through [Write](std::io::Write).
+///
+/// The written bytes are accumulated in a buffer and stored into the node
+/// once by [DataBuilder::finish], so [flush](std::io::Write::flush) does
+/// nothing.
+///
+/// ```
+/// use plist_plus2::DataBuilder;
+///
+/// let mut builder = DataBuilder::new();
+/// std::io::copy(&mut &b"chunk"[..], &mut builder).unwrap();
+/// assert_eq!(builder.finish().as_bytes(), b"chunk");
+/// ```
+#[derive(Debug, Default)]
+pub struct DataBuilder<'a> {
+    data: Data<'a>,
+    buffer: Vec<u8>,
+}
+
+impl<'a> DataBuilder<'a> {
+    /// Creates a builder of an empty data node.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder that appends to an existing data node.
+    pub fn from_data(data: Data<'a>) -> Self {
+        let buffer = data.to_vec();
+        Self { data, buffer }
+    }
+
+    /// Returns the bytes written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Stores the written bytes into the data node and returns it.
+    pub fn finish(mut self) -> Data<'a> {
+        self.data.set(&self.buffer);
+        self.data
+    }
+}
+
+impl std::io::Write for DataBuilder<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 impl From<Vec<u8>> for Data<'_> {
     fn from(bytes: Vec<u8>) -> Self {
         Data::new(&bytes)
@@ -112,6 +196,85 @@ impl std::fmt::Debug for Data<'_> {
     }
 }
 
+impl Value<'_> {
+    /// Stores an arbitrary-precision integer as a tagged data node.
+    ///
+    /// Plist integers are limited to 64 bits, so bigger numbers are kept as
+    /// [Data] instead: the ASCII tag [BIG_INTEGER_TAG] (`BIGINT:`) followed by
+    /// the number as big-endian two's complement bytes, exactly as given.
+    /// Read it back with [Value::as_big_integer].
+    pub fn big_integer(bytes: &[u8]) -> Self {
+        let mut data = Vec::with_capacity(BIG_INTEGER_TAG.len() + bytes.len());
+        data.extend_from_slice(BIG_INTEGER_TAG);
+        data.extend_from_slice(bytes);
+        Data::new(&data).into()
+    }
+
+    /// Returns the big-endian two's complement bytes of an integer stored with
+    /// [Value::big_integer], or [None] if the value isn't a data node
+    /// starting with [BIG_INTEGER_TAG].
+    pub fn as_big_integer(&self) -> Option<&[u8]> {
+        self.as_data()?.as_bytes().strip_prefix(BIG_INTEGER_TAG)
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes with the standard base64 alphabet and padding.
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &b)| group | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3F;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes standard base64 with optional padding, ignoring ASCII whitespace.
+/// Returns [None] if the input is malformed.
+pub(crate) fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() / 4 * 3);
+    let mut group = 0u32;
+    let mut bits = 0;
+    let mut chars = 0;
+    let mut padding = 0;
+    for b in encoded.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        if b == b'=' {
+            padding += 1;
+            continue;
+        }
+        if padding > 0 {
+            return None;
+        }
+        let index = BASE64_ALPHABET.iter().position(|&c| c == b)?;
+        group = group << 6 | index as u32;
+        bits += 6;
+        chars += 1;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+    // A single character can't encode a byte, and the unused bits must be zero
+    let padded = padding == 0 || (padding <= 2 && (chars + padding) % 4 == 0);
+    if bits >= 6 || group != 0 || !padded {
+        return None;
+    }
+    Some(decoded)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,4 +289,72 @@ mod tests {
         p.set(&DATA2);
         assert_eq!(p.as_bytes(), DATA2);
     }
+
+    #[test]
+    fn data_write_to() {
+        let data = Data::new(&DATA1);
+        let mut sink = Vec::new();
+        assert_eq!(data.write_to(&mut sink).unwrap(), 5);
+        assert_eq!(sink, DATA1);
+
+        let mut full = [0u8; 2];
+        assert_eq!(data.write_to(&mut &mut full[..]), Err(Error::IO));
+    }
+
+    #[test]
+    fn data_builder() {
+        use std::io::Write;
+
+        let mut builder = DataBuilder::new();
+        builder.write_all(&DATA1).unwrap();
+        builder.write_all(&[]).unwrap();
+        builder.flush().unwrap();
+        assert_eq!(builder.as_bytes(), DATA1);
+        std::io::copy(&mut &DATA2[..], &mut builder).unwrap();
+        let data = builder.finish();
+        assert_eq!(data.as_bytes(), [DATA1, DATA2].concat());
+
+        let mut builder = DataBuilder::from_data(data);
+        builder.write_all(b"!").unwrap();
+        assert_eq!(builder.finish().len(), 11);
+    }
+
+    #[test]
+    fn base64() {
+        for bytes in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let encoded = encode_base64(bytes);
+            assert_eq!(decode_base64(&encoded).unwrap(), bytes, "{encoded}");
+        }
+        assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(decode_base64("Zm9v\nYmE=").unwrap(), b"fooba");
+        assert_eq!(decode_base64("Zm8").unwrap(), b"fo");
+        assert_eq!(decode_base64("Zm9="), None);
+        assert_eq!(decode_base64("Z"), None);
+        assert_eq!(decode_base64("Zm=8"), None);
+        assert_eq!(decode_base64("Zm8!"), None);
+    }
+
+    #[test]
+    fn data_base64() {
+        let data = Data::from_base64("AQIDBAU=").unwrap();
+        assert_eq!(data.as_bytes(), DATA1);
+        assert_eq!(data.to_base64(), "AQIDBAU=");
+        assert_eq!(Data::new(&DATA2).to_base64(), "BQQDAgE=");
+        assert_eq!(Data::from_base64("AQID*").unwrap_err(), Error::Parse);
+    }
+
+    #[test]
+    fn big_integer() {
+        let big = i128::MIN + 12345;
+        let value = Value::big_integer(&big.to_be_bytes());
+        assert_eq!(value.as_data().unwrap().len(), 7 + 16);
+        let bytes = value.as_big_integer().unwrap();
+        assert_eq!(i128::from_be_bytes(bytes.try_into().unwrap()), big);
+
+        let parsed = crate::from_memory(&value.to_bytes().unwrap()).unwrap();
+        assert_eq!(parsed.as_big_integer(), Some(&big.to_be_bytes()[..]));
+        assert_eq!(Value::from(Data::new(&DATA1)).as_big_integer(), None);
+        assert_eq!(Value::from(1).as_big_integer(), None);
+    }
 }
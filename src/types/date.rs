@@ -1,4 +1,4 @@
-use crate::{Value, unsafe_bindings};
+use crate::{Error, Value, unsafe_bindings};
 use std::time::{Duration, SystemTime};
 
 const MAC_EPOCH: u64 = 978307200; // 01/01/2001
@@ -71,6 +71,160 @@ impl Date<'_> {
     }
 }
 
+/// The range of microseconds since the Unix Epoch that libplist can store:
+/// the seconds since 01/01/2001 are kept in 32 bits.
+const MIN_MICROS: i64 = (MAC_EPOCH as i64 + i32::MIN as i64) * 1000000;
+const MAX_MICROS: i64 = (MAC_EPOCH as i64 + i32::MAX as i64) * 1000000 + 999999;
+
+impl Date<'_> {
+    /// Parses an RFC 3339 timestamp, e.g. `2019-01-04T21:00:00Z`
+    /// or `2019-01-05T00:00:00.5+03:00`.
+    ///
+    /// Fractions of a second are kept up to microseconds. Unlike [Date::new],
+    /// dates before the Unix Epoch are supported. Returns [Error::Parse] if
+    /// the timestamp is malformed and [Error::InvalidArg] if the date is out
+    /// of the range libplist can store (1932-12-13 to 2069-01-19).
+    pub fn from_rfc3339(s: &str) -> Result<Self, Error> {
+        let micros = parse_rfc3339(s).ok_or(Error::Parse)?;
+        Self::from_unix_micros(micros).ok_or(Error::InvalidArg)
+    }
+
+    /// Formats the date as an RFC 3339 timestamp in UTC, e.g.
+    /// `2019-01-04T21:00:00Z`. Microseconds are added only if they aren't zero.
+    pub fn to_rfc3339(&self) -> String {
+        let micros = self.unix_micros();
+        let secs = micros.div_euclid(1000000);
+        let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+        let time = secs.rem_euclid(86400);
+        let mut formatted = format!(
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+            time / 3600,
+            time % 3600 / 60,
+            time % 60
+        );
+        let subsec = micros.rem_euclid(1000000);
+        if subsec != 0 {
+            formatted.push_str(&format!(".{subsec:06}"));
+        }
+        formatted.push('Z');
+        formatted
+    }
+
+    /// Creates a date node from microseconds since the Unix Epoch,
+    /// or returns [None] if libplist can't store the date.
+    pub(crate) fn from_unix_micros(micros: i64) -> Option<Self> {
+        if !(MIN_MICROS..=MAX_MICROS).contains(&micros) {
+            return None;
+        }
+        let micros = micros - MAC_EPOCH as i64 * 1000000;
+        let (sec, usec) = (micros.div_euclid(1000000), micros.rem_euclid(1000000));
+        let pointer = unsafe { unsafe_bindings::plist_new_date(sec as i32, usec as i32) };
+        Some(Self {
+            pointer,
+            false_drop: false,
+            phantom: std::marker::PhantomData,
+        })
+    }
+
+    /// Returns the microseconds since the Unix Epoch, which are negative
+    /// for earlier dates.
+    pub(crate) fn unix_micros(&self) -> i64 {
+        let mut sec = 0;
+        let mut usec = 0;
+        unsafe { unsafe_bindings::plist_get_date_val(self.pointer, &mut sec, &mut usec) };
+        (MAC_EPOCH as i64 + sec as i64) * 1000000 + usec as i64
+    }
+}
+
+/// Parses an RFC 3339 timestamp into microseconds since the Unix Epoch.
+fn parse_rfc3339(s: &str) -> Option<i64> {
+    let number = |part: &str| -> Option<i64> {
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse().ok())?
+    };
+    let (date, time) = s.split_once(['T', 't', ' '])?;
+    let (year, month, day) = match date.split('-').collect::<Vec<_>>()[..] {
+        [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+            (number(year)?, number(month)?, number(day)?)
+        }
+        _ => return None,
+    };
+    let (time, offset) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let at = time.rfind(['+', '-'])?;
+        let (time, zone) = time.split_at(at);
+        let sign = if zone.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = zone[1..].split_once(':')?;
+        if hours.len() != 2 || minutes.len() != 2 {
+            return None;
+        }
+        let (hours, minutes) = (number(hours)?, number(minutes)?);
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        (time, sign * (hours * 3600 + minutes * 60))
+    };
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) if !fraction.is_empty() && fraction.len() <= 9 => {
+            let padded = format!("{:0<6}", &fraction[..fraction.len().min(6)]);
+            number(fraction)?;
+            (time, number(&padded)?)
+        }
+        Some(_) => return None,
+        None => (time, 0),
+    };
+    let (hour, minute, second) = match time.split(':').collect::<Vec<_>>()[..] {
+        [hour, minute, second] if hour.len() == 2 && minute.len() == 2 && second.len() == 2 => {
+            (number(hour)?, number(minute)?, number(second)?)
+        }
+        _ => return None,
+    };
+    let valid = (1..=12).contains(&month)
+        && (1..=31).contains(&day)
+        && hour < 24
+        && minute < 60
+        && second <= 60;
+    if !valid {
+        return None;
+    }
+    let secs =
+        days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+    Some(secs * 1000000 + fraction)
+}
+
+/// Returns the number of days since the Unix Epoch for a proleptic
+/// Gregorian date.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Returns the year, month and day of a day counted from the Unix Epoch.
+/// The inverse of [days_from_civil].
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
 impl From<Duration> for Date<'_> {
     fn from(value: Duration) -> Self {
         Date::new(value)
@@ -108,12 +262,109 @@ impl PartialEq for Date<'_> {
     }
 }
 
+impl Eq for Date<'_> {}
+
+/// Dates are ordered chronologically. The comparison uses the signed
+/// microseconds since the Unix Epoch rather than [Date::get], so dates
+/// before 1970 are ordered correctly too.
+impl Ord for Date<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.unix_micros().cmp(&other.unix_micros())
+    }
+}
+
+impl PartialOrd for Date<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Default for Date<'_> {
     fn default() -> Self {
         Duration::default().into()
     }
 }
 
+#[cfg(feature = "chrono")]
+impl Date<'_> {
+    /// Converts the date into a [chrono::DateTime] in UTC, keeping the microseconds.
+    ///
+    /// Unlike [Date::get], this works for dates before the Unix Epoch too,
+    /// since the seconds stored by libplist are signed.
+    pub fn to_chrono(&self) -> chrono::DateTime<chrono::Utc> {
+        // Any date libplist can store is well within chrono's range
+        chrono::DateTime::from_timestamp_micros(self.unix_micros()).unwrap()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Date<'_> {
+    /// Creates a date node keeping the microseconds, nanoseconds are truncated.
+    ///
+    /// Dates before the Unix Epoch are supported. libplist stores the
+    /// seconds since 01/01/2001 in 32 bits, so dates outside of the range
+    /// from 1932-12-13 to 2069-01-19 are clamped to its bounds.
+    fn from(value: chrono::DateTime<chrono::Utc>) -> Self {
+        let micros = value.timestamp_micros().clamp(MIN_MICROS, MAX_MICROS);
+        Self::from_unix_micros(micros).unwrap()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Value<'_> {
+    fn from(value: chrono::DateTime<chrono::Utc>) -> Self {
+        Date::from(value).into()
+    }
+}
+
+/// A range of dates, from `start` (inclusive) to `end` (exclusive),
+/// like [std::ops::Range].
+///
+/// # Example
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use plist_plus2::{Date, DateRange};
+///
+/// let now = SystemTime::now();
+/// let last_hour = DateRange::from(now - Duration::from_secs(3600)..now);
+/// assert!(!last_hour.contains(&Date::from(now)));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct DateRange<'a> {
+    /// The lower bound of the range (inclusive).
+    pub start: Date<'a>,
+    /// The upper bound of the range (exclusive).
+    pub end: Date<'a>,
+}
+
+impl<'a> DateRange<'a> {
+    /// Creates a new range of dates.
+    pub fn new(start: impl Into<Date<'a>>, end: impl Into<Date<'a>>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    /// Returns `true` if the date is contained in the range.
+    pub fn contains(&self, date: &Date) -> bool {
+        let date = date.get();
+        self.start.get() <= date && date < self.end.get()
+    }
+}
+
+impl From<std::ops::Range<SystemTime>> for DateRange<'_> {
+    fn from(range: std::ops::Range<SystemTime>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl From<std::ops::Range<Duration>> for DateRange<'_> {
+    fn from(range: std::ops::Range<Duration>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
 #[cfg(feature = "clean_debug")]
 impl std::fmt::Debug for Date<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -145,10 +396,91 @@ mod tests {
     #[test]
     fn date_before_mac_epoch() {
         let duration = Duration::from_secs(358860726); // 16 May 1981 at 15:32:06
-        let date = Date::new(duration.clone());
+        let date = Date::new(duration);
         assert_eq!(duration, date.get());
     }
 
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_chrono() {
+        use chrono::{DateTime, Utc};
+
+        let time = DateTime::<Utc>::from_timestamp_micros(1546635600123456).unwrap();
+        let date = Date::from(time);
+        assert_eq!(date.get(), Duration::from_micros(1546635600123456));
+        assert_eq!(date.to_chrono(), time);
+
+        let before_unix = DateTime::<Utc>::from_timestamp_micros(-86400_500000).unwrap();
+        assert_eq!(Date::from(before_unix).to_chrono(), before_unix);
+
+        let far = DateTime::<Utc>::from_timestamp(i64::from(i32::MAX) * 4, 0).unwrap();
+        let clamped = Date::from(far).to_chrono();
+        assert_eq!(clamped.timestamp(), MAC_EPOCH as i64 + i64::from(i32::MAX));
+    }
+
+    #[test]
+    fn date_rfc3339() {
+        let date = Date::from_rfc3339("2019-01-04T21:00:00Z").unwrap();
+        assert_eq!(date.get(), Duration::from_secs(1546635600));
+        assert_eq!(date.to_rfc3339(), "2019-01-04T21:00:00Z");
+
+        let shifted = Date::from_rfc3339("2019-01-05T00:00:00.25+03:00").unwrap();
+        assert_eq!(shifted.get(), Duration::from_micros(1546635600250000));
+        assert_eq!(shifted.to_rfc3339(), "2019-01-04T21:00:00.250000Z");
+
+        let old = Date::from_rfc3339("1969-07-20T20:17:40Z").unwrap();
+        assert_eq!(old.to_rfc3339(), "1969-07-20T20:17:40Z");
+
+        for malformed in [
+            "2019-01-04",
+            "2019-01-04T21:00:00",
+            "2019-13-04T21:00:00Z",
+            "2019-01-04T21:00Z",
+            "2019-01-04T21:00:00.Z",
+            "2019-01-04T21:00:00+3:00",
+        ] {
+            assert_eq!(
+                Date::from_rfc3339(malformed),
+                Err(Error::Parse),
+                "{malformed}"
+            );
+        }
+        assert_eq!(
+            Date::from_rfc3339("2100-01-01T00:00:00Z"),
+            Err(Error::InvalidArg)
+        );
+    }
+
+    #[test]
+    fn date_ord() {
+        let before_unix = Date::from_rfc3339("1960-06-01T00:00:00Z").unwrap();
+        let before_mac = Date::new(Duration::from_secs(MAC_EPOCH - 1));
+        let mac = Date::new(Duration::from_secs(MAC_EPOCH));
+        let later = Date::new(Duration::from_micros(MAC_EPOCH * 1000000 + 1));
+        assert!(before_unix < before_mac);
+        assert!(before_mac < mac);
+        assert!(mac < later);
+        assert_eq!(mac.cmp(&mac.clone()), std::cmp::Ordering::Equal);
+
+        let mut sorted = [
+            later.clone(),
+            before_mac.clone(),
+            mac.clone(),
+            before_unix.clone(),
+        ];
+        sorted.sort();
+        assert_eq!(sorted, [before_unix, before_mac, mac, later]);
+    }
+
+    #[test]
+    fn date_range() {
+        let range = DateRange::from(Duration::from_secs(100)..Duration::from_secs(200));
+        assert!(range.contains(&Date::new(Duration::from_secs(100))));
+        assert!(range.contains(&Date::new(Duration::from_secs(150))));
+        assert!(!range.contains(&Date::new(Duration::from_secs(200))));
+        assert!(!range.contains(&Date::new(Duration::from_secs(99))));
+    }
+
     #[test]
     fn set_random_date() {
         let timestamp = 1546635600123456; // Jan 04 2019 21:00:00.123456
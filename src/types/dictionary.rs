@@ -1,4 +1,4 @@
-use crate::{Value, Node, unsafe_bindings};
+use crate::{Error, Node, Value, ValueType, unsafe_bindings};
 use core::ffi::c_void;
 use std::ffi::CString;
 
@@ -6,7 +6,8 @@ use super::{Item, ItemMut, Key};
 
 crate::impl_node!(
     /// A dictionary plist node.
-    Dictionary
+    Dictionary,
+    children: super::ChildCache
 );
 
 impl<'a> Dictionary<'a> {
@@ -16,9 +17,66 @@ impl<'a> Dictionary<'a> {
             pointer: unsafe { unsafe_bindings::plist_new_dict() },
             false_drop: false,
             phantom: std::marker::PhantomData,
+            children: Default::default(),
         }
     }
 
+    /// Creates a dictionary from a map of keys and values.
+    ///
+    /// Unlike [Dictionary::insert], this function doesn't panic: it returns
+    /// [Error::InvalidArg] if any of the keys contains an internal 0 byte.
+    /// The order of entries follows the iteration order of the map.
+    pub fn try_from_map<'b, S>(
+        map: std::collections::HashMap<String, Value<'b>, S>,
+    ) -> Result<Self, Error> {
+        if map.keys().any(|key| key.contains('\0')) {
+            return Err(Error::InvalidArg);
+        }
+        let mut dict = Self::new();
+        for (key, value) in map {
+            dict.insert(key, value);
+        }
+        Ok(dict)
+    }
+
+    /// Creates a dictionary from parallel slices of keys and values,
+    /// pairing them up by position.
+    ///
+    /// Returns [Error::InvalidArg] if the lengths differ or if any of the
+    /// keys contains an internal 0 byte. A repeated key keeps its last value.
+    pub fn from_columns<'b>(keys: &[&str], values: Vec<Value<'b>>) -> Result<Self, Error> {
+        if keys.len() != values.len() || keys.iter().any(|key| key.contains('\0')) {
+            return Err(Error::InvalidArg);
+        }
+        let mut dict = Self::new();
+        for (key, value) in keys.iter().zip(values) {
+            dict.insert(*key, value);
+        }
+        Ok(dict)
+    }
+
+    /// Creates a dictionary from key-value pairs, returning [Error::InvalidArg]
+    /// on the first repeated key instead of overwriting its value like
+    /// [FromIterator] does. A key with an internal 0 byte is rejected
+    /// the same way.
+    pub fn try_from_pairs_unique<'b, I, K, V>(pairs: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<Value<'b>>,
+    {
+        let mut keys = std::collections::HashSet::new();
+        let mut dict = Self::new();
+        for (key, value) in pairs {
+            let key = key.into();
+            if key.contains('\0') || !keys.insert(key.clone()) {
+                return Err(Error::InvalidArg);
+            }
+            dict.insert(key, value);
+        }
+        Ok(dict)
+    }
+
     /// Returns the number of elements in the dictionary.
     pub fn len(&self) -> u32 {
         unsafe { unsafe_bindings::plist_dict_get_size(self.pointer) }
@@ -58,9 +116,44 @@ impl<'a> Dictionary<'a> {
     ///
     /// This function will panic if the supplied string contains an internal 0 byte.
     pub fn get_mut(&mut self, key: impl Into<String>) -> Option<ItemMut<'_>> {
+        self.children.clear();
         self.internal_get(key).map(ItemMut)
     }
 
+    /// Returns the type of the value corresponding to the key or [None]
+    /// if there's not a such key.
+    ///
+    /// No wrapper for the value is created, which makes this a cheap check
+    /// of the dictionary layout. A key containing an internal 0 byte can't
+    /// be present, so it returns [None] as well.
+    pub fn probe(&self, key: &str) -> Option<ValueType> {
+        let key_c_string = CString::new(key).ok()?;
+        let item_ptr =
+            unsafe { unsafe_bindings::plist_dict_get_item(self.pointer, key_c_string.as_ptr()) };
+        if item_ptr.is_null() {
+            return None;
+        }
+        Some(unsafe { unsafe_bindings::plist_get_node_type(item_ptr) }.into())
+    }
+
+    /// Returns an immutable reference to the value corresponding to the key,
+    /// ignoring the letter case of the key, or [None] if there's not a such key.
+    ///
+    /// The exact key is looked up first. If it's not found, every key of the
+    /// dictionary is compared case-insensitively, which takes O(n) time.
+    /// When several keys match, the first one in the dictionary order wins.
+    pub fn get_ci(&self, key: &str) -> Option<Item<'_>> {
+        if !key.contains('\0')
+            && let Some(item) = self.get(key)
+        {
+            return Some(item);
+        }
+        let key = key.to_lowercase();
+        self.iter()
+            .find(|(k, _)| k.to_lowercase() == key)
+            .map(|(_, item)| item)
+    }
+
     /// Inserts a key-value pair into the dictionary.
     ///
     /// If the dictionary did have this key present, the value is updated,
@@ -70,6 +163,7 @@ impl<'a> Dictionary<'a> {
     ///
     /// This function will panic if the supplied string contains an internal 0 byte.
     pub fn insert<'b>(&mut self, key: impl Into<String>, value: impl Into<Value<'b>>) {
+        self.children.clear();
         let key_c_string = CString::new(key.into()).unwrap();
         let mut value = value.into();
         value.as_node_mut().set_false_drop(true);
@@ -82,12 +176,65 @@ impl<'a> Dictionary<'a> {
         }
     }
 
+    /// Returns a mutable reference to the value corresponding to the key.
+    /// If there's no such key, the value produced by `f` is inserted first.
+    ///
+    /// `f` is called only when the key is missing.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the supplied string contains an internal 0 byte.
+    pub fn get_or_insert_with<'s, 'b>(
+        &'s mut self,
+        key: impl Into<String>,
+        f: impl FnOnce() -> Value<'b>,
+    ) -> ItemMut<'s> {
+        self.children.clear();
+        let key = key.into();
+        if self.internal_get(key.as_str()).is_none() {
+            self.insert(key.as_str(), f());
+        }
+        self.internal_get(key).map(ItemMut).unwrap()
+    }
+
+    /// Returns the entry of the key for in-place manipulation,
+    /// like [HashMap::entry](std::collections::HashMap::entry).
+    ///
+    /// ```
+    /// use plist_plus2::{Dictionary, Value};
+    ///
+    /// let mut counts = Dictionary::new();
+    /// for word in ["pig", "cow", "pig"] {
+    ///     counts
+    ///         .entry(word)
+    ///         .and_modify(|count| {
+    ///             let next = count.as_integer().unwrap().as_singed() + 1;
+    ///             count.replace_with(&next.into());
+    ///         })
+    ///         .or_insert(1);
+    /// }
+    /// assert_eq!(*counts.get("pig").unwrap(), Value::from(2));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the supplied string contains an internal 0 byte.
+    pub fn entry(&mut self, key: impl Into<String>) -> DictEntry<'_, 'a> {
+        let key = key.into();
+        assert!(
+            !key.contains('\0'),
+            "a key can't contain an internal 0 byte"
+        );
+        DictEntry { dict: self, key }
+    }
+
     /// Removes a key from the dictionary.
     ///
     /// # Panics
     ///
     /// This function will panic if the supplied string contains an internal 0 byte.
     pub fn remove(&mut self, key: impl Into<String>) {
+        self.children.clear();
         let key = key.into();
         if self.get(&key).is_none() {
             return;
@@ -96,16 +243,111 @@ impl<'a> Dictionary<'a> {
         unsafe { unsafe_bindings::plist_dict_remove_item(self.pointer, key.as_ptr()) }
     }
 
+    /// Removes a key from the dictionary, returning the value at the key
+    /// if the key was previously in the dictionary.
+    ///
+    /// The value is copied before being removed from the dictionary,
+    /// so it gets a lifetime of a caller.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the supplied string contains an internal 0 byte.
+    pub fn pop<'b>(&mut self, key: &str) -> Option<Value<'b>> {
+        let value = self.get(key)?.clone();
+        self.remove(key);
+        Some(value)
+    }
+
+    /// Removes the entries for which the predicate returns `true` and returns
+    /// them in the dictionary order.
+    ///
+    /// The matching entries are collected first and removed afterwards,
+    /// so the predicate sees every entry of the unchanged dictionary.
+    /// The returned values are copies with a lifetime of a caller.
+    pub fn drain_filter<'b, F>(&mut self, mut f: F) -> Vec<(String, Value<'b>)>
+    where
+        F: FnMut(&str, &Value) -> bool,
+    {
+        let drained: Vec<(String, Value<'b>)> = self
+            .iter()
+            .filter(|(key, value)| f(key, value))
+            .map(|(key, value)| (key, value.clone()))
+            .collect();
+        for (key, _) in &drained {
+            self.remove(key.as_str());
+        }
+        drained
+    }
+
+    /// Retains only the entries for which the predicate returns `true`.
+    ///
+    /// The rejected keys are collected first and removed afterwards,
+    /// so the predicate sees every entry of the unchanged dictionary.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &Value) -> bool,
+    {
+        self.children.clear();
+        let rejected: Vec<CString> = self
+            .iter()
+            .filter(|(key, value)| !f(key, value))
+            .map(|(key, _)| CString::new(key).unwrap())
+            .collect();
+        for key in rejected {
+            unsafe { unsafe_bindings::plist_dict_remove_item(self.pointer, key.as_ptr()) }
+        }
+    }
+
     /// Merges a dictionary into another.
     ///
     /// This will copy all key/value pairs from the source dictionary to the current dictionary,
     /// overwriting any existing key/value pairs that are already present in target.
     pub fn merge(&mut self, from: &Dictionary) {
+        self.children.clear();
         // plist_dict_merge copies every node from another dictionary,
         // so passing a simple reference is fine
         unsafe { unsafe_bindings::plist_dict_merge(&mut self.pointer(), from.pointer()) }
     }
 
+    /// Merges a dictionary into another, letting a closure resolve conflicts.
+    ///
+    /// Keys missing from the current dictionary are copied from `from`.
+    /// For keys present in both, `resolver` gets the key, the existing value
+    /// and the incoming one, and the value it returns is stored.
+    pub fn merge_with<'b, F>(&mut self, from: &Dictionary, mut resolver: F)
+    where
+        F: FnMut(&str, &Value, &Value) -> Value<'b>,
+    {
+        for (key, incoming) in from {
+            let resolved = match self.get(key.as_str()) {
+                Some(existing) => resolver(&key, &existing, &incoming),
+                None => incoming.clone(),
+            };
+            self.insert(key, resolved);
+        }
+    }
+
+    /// Merges a dictionary into another, resolving conflicts with a policy.
+    ///
+    /// Keys missing from the current dictionary are copied from `from`,
+    /// and the [MergePolicy] decides what happens to keys present in both.
+    /// This is [Dictionary::merge_with] with [MergePolicy::resolve]
+    /// as the resolver.
+    ///
+    /// ```rust
+    /// use plist_plus2::{MergePolicy, dict};
+    ///
+    /// let mut config = dict!("name" => "app", "window" => dict!("width" => 800, "height" => 600));
+    /// let overrides = dict!("window" => dict!("width" => 1024));
+    /// config.merge_with_policy(&overrides, MergePolicy::Recursive);
+    /// assert_eq!(config, dict!("name" => "app", "window" => dict!("width" => 1024, "height" => 600)));
+    /// ```
+    pub fn merge_with_policy(&mut self, from: &Dictionary, policy: MergePolicy) {
+        self.merge_with(from, |key, existing, incoming| {
+            policy.resolve(key, existing, incoming)
+        });
+    }
+
     /// Creates an immutable iterator over an dictionary.
     pub fn iter(&self) -> Iter<'_, 'a> {
         self.into_iter()
@@ -139,6 +381,113 @@ impl<'a> Dictionary<'a> {
     }
 }
 
+/// How [Dictionary::merge_with_policy] resolves keys present in both dictionaries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MergePolicy {
+    /// The incoming value replaces the existing one, like [Dictionary::merge].
+    #[default]
+    Overwrite,
+    /// The existing value is kept.
+    KeepExisting,
+    /// Nested dictionaries are merged recursively with the same policy,
+    /// any other conflict is resolved by taking the incoming value.
+    ///
+    /// This layers an override dictionary on top of a base one.
+    Recursive,
+}
+
+impl MergePolicy {
+    /// Returns the value the policy stores for a key present in both
+    /// dictionaries.
+    ///
+    /// It can be called from a resolver of [Dictionary::merge_with]
+    /// to fall back to a policy for the keys that need no special care.
+    pub fn resolve<'b>(&self, _key: &str, existing: &Value, incoming: &Value) -> Value<'b> {
+        match (self, existing, incoming) {
+            (MergePolicy::KeepExisting, _, _) => existing.clone(),
+            (MergePolicy::Recursive, Value::Dictionary(existing), Value::Dictionary(incoming)) => {
+                let mut merged = existing.clone();
+                merged.merge_with_policy(incoming, *self);
+                merged.into()
+            }
+            _ => incoming.clone(),
+        }
+    }
+}
+
+/// An entry of a [Dictionary] returned by [Dictionary::entry].
+#[derive(Debug)]
+pub struct DictEntry<'s, 'a> {
+    dict: &'s mut Dictionary<'a>,
+    key: String,
+}
+
+impl<'s> DictEntry<'s, '_> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns a mutable reference to the value of the entry,
+    /// inserting `default` first if the key is missing.
+    pub fn or_insert<'b>(self, default: impl Into<Value<'b>>) -> ItemMut<'s> {
+        let default = default.into();
+        self.dict.get_or_insert_with(self.key, || default)
+    }
+
+    /// Returns a mutable reference to the value of the entry,
+    /// inserting the value produced by `f` first if the key is missing.
+    ///
+    /// `f` is called only when the key is missing.
+    pub fn or_insert_with<'b>(self, f: impl FnOnce() -> Value<'b>) -> ItemMut<'s> {
+        self.dict.get_or_insert_with(self.key, f)
+    }
+
+    /// Calls `f` with the value of the entry if the key is present.
+    ///
+    /// Scalars can be changed in place with [Value::replace_with]. A value
+    /// assigned to the reference replaces the one in the dictionary.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut Value),
+    {
+        self.dict.children.clear();
+        let key_c_string = CString::new(self.key.as_str()).unwrap();
+        let pointer = unsafe {
+            unsafe_bindings::plist_dict_get_item(self.dict.pointer, key_c_string.as_ptr())
+        };
+        if !pointer.is_null() {
+            let mut value = unsafe { crate::from_pointer(pointer) };
+            value.as_node_mut().set_false_drop(true);
+            f(&mut value);
+            if let Some(value) = super::replaced(value, pointer) {
+                self.dict.insert(self.key.as_str(), value);
+            }
+        }
+        self
+    }
+}
+
+/// Indexing returns a reference that lives as long as the dictionary
+/// is borrowed.
+///
+/// # Panics
+/// Panics if the key isn't present or contains an internal 0 byte.
+/// Use [Dictionary::get] for a non-panicking lookup.
+impl<'a> std::ops::Index<&str> for Dictionary<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        let key_c_string = CString::new(key).unwrap();
+        let item_ptr =
+            unsafe { unsafe_bindings::plist_dict_get_item(self.pointer, key_c_string.as_ptr()) };
+        if item_ptr.is_null() {
+            panic!("key `{key}` not found in the dictionary");
+        }
+        self.children.get(self.pointer, item_ptr)
+    }
+}
+
 impl Default for Dictionary<'_> {
     fn default() -> Self {
         Self::new()
@@ -212,6 +561,7 @@ impl<'a, 'b> IntoIterator for &'a mut Dictionary<'b> {
     type IntoIter = IterMut<'a, 'b>;
 
     fn into_iter(self) -> Self::IntoIter {
+        self.children.clear();
         let mut iter_pointer = unsafe { std::mem::zeroed() };
         unsafe { unsafe_bindings::plist_array_new_iter(self.pointer(), &mut iter_pointer) }
         IterMut {
@@ -221,6 +571,47 @@ impl<'a, 'b> IntoIterator for &'a mut Dictionary<'b> {
     }
 }
 
+/// An owning dictionary iterator, created by calling `into_iter` on a [Dictionary].
+///
+/// `libplist` can't detach children from their parent, so every value
+/// is yielded as a deep copy, costing an allocation per node of its
+/// subtree. The keys are collected when the iterator is created and the
+/// dictionary itself is freed when the iterator is dropped. Iterate over
+/// `&dict` to avoid the copies.
+#[derive(Debug)]
+pub struct IntoIter<'a> {
+    dict: Dictionary<'a>,
+    keys: std::vec::IntoIter<String>,
+}
+
+impl<'a> IntoIterator for Dictionary<'a> {
+    type Item = (String, Value<'a>);
+    type IntoIter = IntoIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let keys: Vec<String> = self.iter().map(|(key, _)| key).collect();
+        IntoIter {
+            dict: self,
+            keys: keys.into_iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for IntoIter<'a> {
+    type Item = (String, Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        let pointer = self.dict.get(key.as_str())?.pointer();
+        let value = unsafe { crate::from_pointer(unsafe_bindings::plist_copy(pointer)) };
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
 fn iter_next<'a>(dict: &Dictionary<'a>, iter_pointer: *mut c_void) -> Option<(Key<'a>, Value<'a>)> {
     let mut value_ptr = unsafe { std::mem::zeroed() };
     // Getting next item in dictionary
@@ -294,6 +685,25 @@ where
     }
 }
 
+/// Collects key-value pairs into a dictionary. A repeated key keeps its last value.
+///
+/// # Panics
+///
+/// Panics if any of the keys contains an internal 0 byte.
+impl<'b, K, V> FromIterator<(K, V)> for Dictionary<'_>
+where
+    K: Into<String>,
+    V: Into<Value<'b>>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut dict = Self::new();
+        for (key, value) in iter {
+            dict.insert(key, value);
+        }
+        dict
+    }
+}
+
 /// A helper macro for creating dictionaries.
 ///
 /// # Example
@@ -317,19 +727,363 @@ macro_rules! dict {
     };
 }
 
+/// A fallible version of [dict!].
+///
+/// Every value can be either anything convertible into a [Value] or
+/// a [Result] of it (see [TryIntoValue](crate::TryIntoValue)). The first error
+/// is returned and the rest of the values aren't evaluated. A key containing
+/// an internal 0 byte results in [Error::InvalidArg] instead of a panic.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{Error, PString, dict, try_dict};
+///
+/// let name = PString::try_new("Pip");
+/// let pet = try_dict!("name" => name, "age" => 7).unwrap();
+/// assert_eq!(pet, dict!("name" => "Pip", "age" => 7));
+///
+/// let result = try_dict!("name" => PString::try_new("P\0ip"));
+/// assert_eq!(result.unwrap_err(), Error::InvalidArg);
+/// ```
+#[macro_export]
+macro_rules! try_dict {
+    () => {::std::result::Result::<$crate::Dictionary, $crate::Error>::Ok($crate::Dictionary::new())};
+    (
+        $($key:expr => $val:expr),+ $(,)?
+    ) => {
+        'build: {
+            let mut dict = $crate::Dictionary::new();
+            $(
+                let key: ::std::string::String = ::std::convert::Into::into($key);
+                if key.contains('\0') {
+                    break 'build ::std::result::Result::Err($crate::Error::InvalidArg);
+                }
+                match $crate::TryIntoValue::try_into_value($val) {
+                    ::std::result::Result::Ok(value) => dict.insert(key, value),
+                    ::std::result::Result::Err(error) => {
+                        break 'build ::std::result::Result::Err(error);
+                    }
+                }
+            )+
+            ::std::result::Result::<$crate::Dictionary, $crate::Error>::Ok(dict)
+        }
+    };
+}
+
+/// A helper macro for creating dictionaries whose keys are identifiers.
+///
+/// It's a more readable alternative to [dict!] for record-like dictionaries.
+/// Use [dict!] when the keys are computed at runtime.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::{dict, record};
+///
+/// let person = record! { name: "Pip", age: 7 };
+/// assert_eq!(person, dict!("name" => "Pip", "age" => 7));
+/// ```
+#[macro_export]
+macro_rules! record {
+    () => {$crate::Dictionary::new()};
+    (
+        $($key:ident : $val:expr),+ $(,)?
+    ) => {
+        {
+            let mut dict = $crate::Dictionary::new();
+            $(dict.insert(stringify!($key), $val);)+
+            dict
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
     const ARRAY: [u64; 4] = [0, 1, 2, 3];
     const KEYS: [&str; 4] = ["First", "Second", "Third", "Fourth"];
 
+    #[test]
+    fn dict_from_columns() {
+        let values = ARRAY.iter().map(|&n| Value::from(n)).collect();
+        let dict = Dictionary::from_columns(&KEYS, values).unwrap();
+        assert_eq!(dict.len(), 4);
+        assert_eq!(dict["Third"], Value::from(2u64));
+
+        let short = vec![Value::from(1), Value::from(2)];
+        assert_eq!(
+            Dictionary::from_columns(&KEYS, short),
+            Err(Error::InvalidArg)
+        );
+        let bad_key = vec![Value::from(1)];
+        assert_eq!(
+            Dictionary::from_columns(&["a\0b"], bad_key),
+            Err(Error::InvalidArg)
+        );
+    }
+
+    #[test]
+    fn dict_index_cache_is_cleared() {
+        let mut dict = dict!("count" => 0);
+        for i in 0..100 {
+            assert_eq!(dict["count"], Value::from(i));
+            dict.insert("count", i + 1);
+            dict.insert("other", "x");
+            assert_eq!(dict["other"], Value::from("x"));
+            dict.remove("other");
+        }
+        assert!(dict.children.len() <= 1);
+
+        // Changes through other wrappers of the node don't clear the cache
+        let mut plist: Value = dict.into();
+        for i in 100..200 {
+            assert_eq!(plist.as_dictionary().unwrap()["count"], Value::from(i));
+            plist.set_path("count", i + 1).unwrap();
+        }
+        assert!(plist.as_dictionary().unwrap().children.len() <= 1);
+    }
+
+    #[test]
+    fn dict_entry() {
+        let mut dict = dict!("count" => 1, "list" => array!(1));
+        dict.entry("count")
+            .and_modify(|count| count.replace_with(&Value::from(2)))
+            .or_insert(0);
+        dict.entry("missing")
+            .and_modify(|_| unreachable!())
+            .or_insert_with(|| "new".into());
+        dict.entry("list")
+            .and_modify(|list| *list = array!(1, 2).into());
+        {
+            let entry = dict.entry("flag");
+            assert_eq!(entry.key(), "flag");
+            assert_eq!(*entry.or_insert(false), false.into());
+        }
+        assert_eq!(
+            dict,
+            dict!(
+                "count" => 2,
+                "list" => array!(1, 2),
+                "missing" => "new",
+                "flag" => false
+            )
+        );
+    }
+
+    #[test]
+    fn dict_get_or_insert_with() {
+        let mut dict = dict!("Existing" => dict!("Name" => "pig"));
+        dict.get_or_insert_with("Settings", || Dictionary::new().into())
+            .as_dictionary_mut()
+            .unwrap()
+            .insert("Enabled", true);
+        {
+            let existing = dict.get_or_insert_with("Existing", || unreachable!());
+            assert_eq!(*existing, dict!("Name" => "pig").into());
+        }
+        assert_eq!(
+            dict,
+            dict!(
+                "Existing" => dict!("Name" => "pig"),
+                "Settings" => dict!("Enabled" => true)
+            )
+        );
+    }
+
+    #[test]
+    fn dict_drain_filter() {
+        let mut dict = dict!("tmp_a" => 1, "keep" => 2, "tmp_b" => "b");
+        let drained = dict.drain_filter(|key, _| key.starts_with("tmp_"));
+        assert_eq!(
+            drained,
+            [
+                ("tmp_a".to_string(), Value::from(1)),
+                ("tmp_b".to_string(), Value::from("b"))
+            ]
+        );
+        assert_eq!(dict, dict!("keep" => 2));
+        assert!(dict.drain_filter(|_, _| false).is_empty());
+    }
+
+    #[test]
+    fn dict_from_iter() {
+        let dict: Dictionary = KEYS.iter().zip(ARRAY).map(|(k, v)| (*k, v)).collect();
+        assert_eq!(dict.len(), 4);
+        assert_eq!(dict["Fourth"], Value::from(3u64));
+        let dict: Dictionary = vec![("a".to_string(), 1), ("a".to_string(), 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(dict, dict!("a" => 2));
+    }
+
+    #[test]
+    fn dict_try_from_pairs_unique() {
+        let dict = Dictionary::try_from_pairs_unique(KEYS.iter().copied().zip(ARRAY)).unwrap();
+        assert_eq!(dict.len(), 4);
+        let duplicates = [("a", 1), ("b", 2), ("a", 3)];
+        assert_eq!(
+            Dictionary::try_from_pairs_unique(duplicates),
+            Err(Error::InvalidArg)
+        );
+    }
+
+    #[test]
+    fn dict_retain() {
+        let mut dict = dict!("App.Name" => "pig", "App.Legs" => 4, "Color" => "pink");
+        dict.retain(|key, _| !key.starts_with("App."));
+        assert_eq!(dict, dict!("Color" => "pink"));
+        dict.retain(|_, value| value.as_string().is_none());
+        assert!(dict.is_empty());
+    }
+
+    #[test]
+    fn dict_try_dict() {
+        let names = ["pig", "lamb"];
+        let dict = try_dict!(
+            "first" => PString::try_new(names[0]),
+            "second" => PString::try_new(names[1]),
+            "count" => 2
+        )
+        .unwrap();
+        assert_eq!(
+            dict,
+            dict!("first" => "pig", "second" => "lamb", "count" => 2)
+        );
+
+        let mut evaluated = false;
+        let result = try_dict!(
+            "bad" => PString::try_new("wo\0rm"),
+            "never" => {
+                evaluated = true;
+                1
+            }
+        );
+        assert_eq!(result.unwrap_err(), Error::InvalidArg);
+        assert!(!evaluated);
+        assert_eq!(try_dict!("k\0ey" => 1).unwrap_err(), Error::InvalidArg);
+        assert!(try_dict!().unwrap().is_empty());
+    }
+
+    #[test]
+    fn dict_merge_with() {
+        let mut dict = dict!("pig" => 2, "lamb" => 1);
+        let from = dict!("lamb" => 3, "worm" => 5);
+        dict.merge_with(&from, |_, existing, incoming| {
+            let existing = existing.as_integer().unwrap().as_unsinged();
+            (existing + incoming.as_integer().unwrap().as_unsinged()).into()
+        });
+        assert_eq!(dict, dict!("pig" => 2, "lamb" => 4, "worm" => 5));
+        assert_eq!(from, dict!("lamb" => 3, "worm" => 5));
+    }
+
+    #[test]
+    fn dict_merge_with_policy() {
+        let base = dict!(
+            "name" => "app",
+            "window" => dict!("width" => 800, "height" => 600),
+            "plugins" => array!("a")
+        );
+        let overrides = dict!(
+            "window" => dict!("width" => 1024, "frame" => dict!("x" => 0)),
+            "plugins" => array!("b"),
+            "debug" => true
+        );
+
+        let mut dict = base.clone();
+        dict.merge_with_policy(&overrides, MergePolicy::Overwrite);
+        let mut merged = base.clone();
+        merged.merge(&overrides);
+        assert_eq!(dict, merged);
+
+        let mut dict = base.clone();
+        dict.merge_with_policy(&overrides, MergePolicy::KeepExisting);
+        let mut expected = base.clone();
+        expected.insert("debug", true);
+        assert_eq!(dict, expected);
+
+        let mut dict = base.clone();
+        dict.merge_with_policy(&overrides, MergePolicy::Recursive);
+        let expected = dict!(
+            "name" => "app",
+            "window" => dict!("width" => 1024, "height" => 600, "frame" => dict!("x" => 0)),
+            "plugins" => array!("b"),
+            "debug" => true
+        );
+        assert_eq!(dict, expected);
+    }
+
+    #[test]
+    fn dict_index() {
+        let dict = dict!("Author" => "William Shakespeare", "Lines" => array!("To be"));
+        assert_eq!(
+            dict["Author"].as_string().unwrap().as_str(),
+            "William Shakespeare"
+        );
+        assert_eq!(dict["Lines"].as_array().unwrap()[0], Value::from("To be"));
+    }
+
+    #[test]
+    #[should_panic(expected = "key `Title` not found")]
+    fn dict_index_missing() {
+        let dict = dict!("Author" => "William Shakespeare");
+        let _ = &dict["Title"];
+    }
+
     #[test]
     fn dict_test() {
         let b = Boolean::new(false);
         let mut p = Dictionary::new();
         p.insert("b", b);
         let b = p.get("b").unwrap();
-        assert_eq!(b.as_boolean().unwrap().as_bool(), false);
+        assert!(!b.as_boolean().unwrap().as_bool());
+    }
+
+    #[test]
+    fn dict_get_ci() {
+        let plist = dict!("Title" => "Great Expectations", "title" => "Hard Times");
+        let exact = plist.get_ci("title").unwrap();
+        assert_eq!(exact.as_string().unwrap().as_str(), "Hard Times");
+
+        let plist = dict!("Title" => "Great Expectations");
+        let item = plist.get_ci("title").unwrap();
+        assert_eq!(item.as_string().unwrap().as_str(), "Great Expectations");
+        assert!(plist.get_ci("author").is_none());
+    }
+
+    #[test]
+    fn dict_record() {
+        let plist = record! {
+            name: "x",
+            count: 3,
+            tags: array!("a", "b"),
+        };
+        assert_eq!(
+            plist,
+            dict!("name" => "x", "count" => 3, "tags" => array!("a", "b"))
+        );
+    }
+
+    #[test]
+    fn dict_try_from_map() {
+        let mut map = std::collections::HashMap::new();
+        map.insert("name".to_string(), Value::from("x"));
+        map.insert("count".to_string(), Value::from(3));
+        let plist = Dictionary::try_from_map(map).unwrap();
+        assert_eq!(plist, dict!("name" => "x", "count" => 3));
+
+        let mut map = std::collections::HashMap::new();
+        map.insert("valid".to_string(), Value::from(true));
+        map.insert("in\0valid".to_string(), Value::from(false));
+        assert_eq!(Dictionary::try_from_map(map), Err(Error::InvalidArg));
+    }
+
+    #[test]
+    fn dict_pop() {
+        let mut plist = dict!("name" => "x", "count" => 3);
+        let count = plist.pop("count").unwrap();
+        assert_eq!(plist, dict!("name" => "x"));
+        assert!(plist.pop("count").is_none());
+        std::mem::drop(plist);
+        assert_eq!(count.as_integer().unwrap().as_unsinged(), 3);
     }
 
     #[test]
@@ -379,6 +1133,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn dict_into_iter() {
+        let plist: Dictionary = KEYS.into_iter().zip(ARRAY).collect();
+        let mut count = 0;
+        for ((key, value), (i_key, i_value)) in KEYS.into_iter().zip(ARRAY).zip(plist) {
+            assert_eq!(key, i_key);
+            assert_eq!(value, i_value.as_integer().unwrap().as_unsinged());
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+
     #[test]
     fn dict_iter_mut() {
         // Create a new plist dict with dummy values
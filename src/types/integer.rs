@@ -2,6 +2,11 @@ use crate::{Value, unsafe_bindings};
 
 crate::impl_node!(
     /// An integer that can be represented by either an `i64` or a `u64`.
+    ///
+    /// `==` compares the raw `u64` representations, so a signed `-1` equals
+    /// an unsigned [u64::MAX]. An [Ord] telling them apart would disagree
+    /// with that equality, so integers implement neither [Ord] nor
+    /// [PartialOrd]. Sort them with [Integer::cmp_numeric] instead.
     Integer
 );
 
@@ -42,6 +47,47 @@ impl Integer<'_> {
         val
     }
 
+    /// Returns `true` if the integer holds a negative signed value.
+    pub(crate) fn is_negative(&self) -> bool {
+        unsafe { unsafe_bindings::plist_int_val_is_negative(self.pointer) != 0 }
+    }
+
+    /// Returns `true` if the integer is stored in the unsigned representation.
+    ///
+    /// `libplist` only uses a separate unsigned representation for values that
+    /// don't fit into an `i64`, i.e. greater than `i64::MAX`. Values from `0`
+    /// to `i64::MAX` are stored the same way no matter whether they were created
+    /// with [Integer::new_signed] or [Integer::new_unsigned], and they're
+    /// reported as signed.
+    pub fn is_unsigned(&self) -> bool {
+        !self.is_negative() && self.as_unsinged() > i64::MAX as u64
+    }
+
+    /// Returns `true` if the integer is stored in the signed representation,
+    /// i.e. its value fits into an `i64`. The opposite of [Integer::is_unsigned].
+    pub fn is_signed(&self) -> bool {
+        !self.is_unsigned()
+    }
+
+    /// Returns the value with its actual sign, wide enough for both
+    /// `i64` and `u64` values.
+    pub(crate) fn as_i128(&self) -> i128 {
+        if self.is_negative() {
+            self.as_singed() as i128
+        } else {
+            self.as_unsinged() as i128
+        }
+    }
+
+    /// Compares the integers numerically, taking their sign into account,
+    /// so `-1 < 0 < u64::MAX` holds.
+    ///
+    /// Unlike [PartialEq], which compares the raw `u64` representations,
+    /// this tells `-1` and `u64::MAX` apart.
+    pub fn cmp_numeric(&self, other: &Integer) -> std::cmp::Ordering {
+        self.as_i128().cmp(&other.as_i128())
+    }
+
     /// Sets the integer value as a `u64`.
     pub fn set_unsigned(&mut self, value: u64) {
         unsafe { unsafe_bindings::plist_set_uint_val(self.pointer, value) }
@@ -214,4 +260,47 @@ mod tests {
         p.set_signed(UINT2);
         assert_eq!(p.as_singed(), UINT2);
     }
+
+    #[test]
+    fn int_signedness() {
+        let min = Integer::new_signed(i64::MIN);
+        assert!(min.is_signed());
+        assert!(!min.is_unsigned());
+        let max = Integer::new_unsigned(u64::MAX);
+        assert!(max.is_unsigned());
+        assert!(!max.is_signed());
+
+        let copy: Integer = crate::from_memory(&Value::from(max).to_bytes().unwrap())
+            .unwrap()
+            .into_integer()
+            .unwrap();
+        assert!(copy.is_unsigned());
+        assert!(Integer::new_unsigned(5).is_signed());
+    }
+
+    #[test]
+    fn int_cmp_numeric() {
+        use std::cmp::Ordering;
+
+        let minus_one = Integer::new_signed(-1);
+        let zero = Integer::new_unsigned(0);
+        let max = Integer::new_unsigned(u64::MAX);
+        assert_eq!(minus_one.cmp_numeric(&zero), Ordering::Less);
+        assert_eq!(zero.cmp_numeric(&max), Ordering::Less);
+        assert_eq!(minus_one.cmp_numeric(&max), Ordering::Less);
+        assert_eq!(
+            Integer::new_signed(i64::MIN).cmp_numeric(&minus_one),
+            Ordering::Less
+        );
+        assert_eq!(
+            Integer::new_signed(5).cmp_numeric(&Integer::new_unsigned(5)),
+            Ordering::Equal
+        );
+
+        let mut sorted = [max, zero, minus_one];
+        sorted.sort_by(Integer::cmp_numeric);
+        assert_eq!(sorted[0].as_singed(), -1);
+        assert_eq!(sorted[1].as_singed(), 0);
+        assert_eq!(sorted[2].as_unsinged(), u64::MAX);
+    }
 }
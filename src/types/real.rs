@@ -2,6 +2,10 @@ use crate::{Value, unsafe_bindings};
 
 crate::impl_node!(
     /// A real `f64` plist node.
+    ///
+    /// Infinite and NaN values can be stored, but XML plists have no
+    /// representation for them. Check the tree with
+    /// [Value::xml_compatibility] before exporting it to XML.
     Real
 );
 
@@ -24,6 +28,12 @@ impl Real<'_> {
         val
     }
 
+    /// Returns `true` if the value is neither infinite nor NaN,
+    /// so it can be exported to any format.
+    pub fn is_finite(&self) -> bool {
+        self.as_float().is_finite()
+    }
+
     /// Sets the value of the real with the given float.
     pub fn set(&mut self, value: f64) {
         unsafe { unsafe_bindings::plist_set_real_val(self.pointer, value) }
@@ -51,6 +61,18 @@ impl From<f64> for Value<'_> {
     }
 }
 
+impl From<f32> for Real<'_> {
+    fn from(value: f32) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl From<f32> for Value<'_> {
+    fn from(value: f32) -> Self {
+        Real::new(value.into()).into()
+    }
+}
+
 impl From<Real<'_>> for f64 {
     fn from(value: Real<'_>) -> Self {
         value.as_float()
@@ -86,6 +108,7 @@ impl std::fmt::Debug for Real<'_> {
 mod tests {
     use super::*;
 
+    #[allow(clippy::approx_constant)]
     const REAL1: f64 = 3.1415926;
     const REAL2: f64 = 1234.098765;
 
@@ -96,4 +119,13 @@ mod tests {
         p.set(REAL2);
         assert_eq!(p.as_float(), REAL2);
     }
+
+    #[test]
+    fn real_f32_and_finite() {
+        assert_eq!(Real::from(1.5f32).as_float(), 1.5);
+        assert_eq!(Value::from(0.25f32), Value::from(0.25));
+        assert!(Real::new(REAL1).is_finite());
+        assert!(!Real::new(f64::NAN).is_finite());
+        assert!(!Real::from(f32::NEG_INFINITY).is_finite());
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Value, unsafe_bindings};
+use crate::{Error, Value, unsafe_bindings};
 use std::ffi::CString;
 
 crate::impl_node!(
@@ -21,13 +21,38 @@ impl PString<'_> {
         }
     }
 
+    /// Creates a new string plist node, or returns [Error::InvalidArg]
+    /// if the supplied string contains an internal 0 byte.
+    pub fn try_new(string: impl Into<String>) -> Result<Self, Error> {
+        let string = CString::new(string.into())?;
+        Ok(Self {
+            pointer: unsafe { unsafe_bindings::plist_new_string(string.as_ptr() as *const _) },
+            false_drop: false,
+            phantom: std::marker::PhantomData,
+        })
+    }
+
     /// Returns the value of the string.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the string isn't valid UTF-8, which may
+    /// happen with plists produced by other tools. Use [PString::try_as_str]
+    /// to handle this case.
     pub fn as_str(&self) -> &'_ str {
+        self.try_as_str().unwrap()
+    }
+
+    /// Returns the value of the string, or [Error::Parse] if it isn't valid UTF-8.
+    pub fn try_as_str(&self) -> Result<&'_ str, Error> {
+        std::str::from_utf8(self.as_bytes()).map_err(|_| Error::Parse)
+    }
+
+    /// Returns the raw bytes of the string, which are not guaranteed to be valid UTF-8.
+    pub(crate) fn as_bytes(&self) -> &'_ [u8] {
         let mut len = 0;
         let ptr = unsafe { unsafe_bindings::plist_get_string_ptr(self.pointer, &mut len) };
-        let slice = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
-        // TODO: add a check for correct utf-8 encoding?
-        std::str::from_utf8(slice).unwrap()
+        unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) }
     }
 
     /// Sets the value string with the given value.
@@ -119,4 +144,13 @@ mod tests {
         p.set(STRING2);
         assert_eq!(p.as_str(), STRING2);
     }
+
+    #[test]
+    fn string_try_as_str() {
+        assert_eq!(PString::new(STRING1).try_as_str(), Ok(STRING1));
+        let invalid =
+            unsafe { crate::from_pointer(unsafe_bindings::plist_new_string(c"caf\xE9".as_ptr())) };
+        let invalid = invalid.as_string().unwrap();
+        assert_eq!(invalid.try_as_str(), Err(Error::Parse));
+    }
 }
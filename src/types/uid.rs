@@ -1,4 +1,4 @@
-use crate::unsafe_bindings;
+use crate::{Error, unsafe_bindings};
 
 crate::impl_node!(
     /// A plist `uid` plist node. These are found exclusively in plists created by NSKeyedArchiver.
@@ -6,7 +6,15 @@ crate::impl_node!(
 );
 
 impl Uid<'_> {
+    /// The largest uid that can be serialized.
+    ///
+    /// `libplist` writes binary uids with at most 4 bytes, the same way
+    /// NSKeyedArchiver does, so larger values get truncated.
+    pub const MAX: u64 = u32::MAX as u64;
+
     /// Creates a new uid plist node.
+    ///
+    /// The value isn't checked, see [Uid::try_new] for a checked version.
     pub fn new(uid: u64) -> Self {
         Self {
             pointer: unsafe { unsafe_bindings::plist_new_uid(uid) },
@@ -15,6 +23,15 @@ impl Uid<'_> {
         }
     }
 
+    /// Creates a new uid plist node, returning [Error::InvalidArg]
+    /// if the value exceeds [Uid::MAX].
+    pub fn try_new(uid: u64) -> Result<Self, Error> {
+        if uid > Self::MAX {
+            return Err(Error::InvalidArg);
+        }
+        Ok(Self::new(uid))
+    }
+
     /// Returns the value of the uid.
     pub fn get(&self) -> u64 {
         let mut uid = 0;
@@ -39,6 +56,55 @@ impl Uid<'_> {
     }
 }
 
+/// Hands out sequential uids, e.g. for referencing the `$objects`
+/// of an NSKeyedArchiver archive.
+///
+/// # Example
+/// ```rust
+/// use plist_plus2::UidAllocator;
+///
+/// let mut uids = UidAllocator::new(1);
+/// assert_eq!(uids.next().get(), 1);
+/// assert_eq!(uids.next().get(), 2);
+/// assert_eq!(uids.count(), 2);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UidAllocator {
+    base: u64,
+    count: u64,
+}
+
+impl UidAllocator {
+    /// Creates an allocator whose first uid is `base`.
+    pub fn new(base: u64) -> Self {
+        Self { base, count: 0 }
+    }
+
+    /// Returns a new uid, one greater than the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the uids run past [u64::MAX].
+    pub fn next<'a>(&mut self) -> Uid<'a> {
+        let uid = self
+            .base
+            .checked_add(self.count)
+            .expect("uid allocator overflow");
+        self.count += 1;
+        Uid::new(uid)
+    }
+
+    /// Returns the first uid of the sequence.
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Returns the number of uids handed out so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
 impl From<Uid<'_>> for u64 {
     fn from(value: Uid<'_>) -> Self {
         value.get()
@@ -93,3 +159,24 @@ impl std::fmt::Debug for Uid<'_> {
         self.get().fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uid_allocator() {
+        let mut uids = UidAllocator::new(10);
+        let allocated: Vec<u64> = (0..4).map(|_| uids.next().get()).collect();
+        assert_eq!(allocated, [10, 11, 12, 13]);
+        assert_eq!(uids.count(), 4);
+        assert_eq!(uids.base(), 10);
+        assert_eq!(UidAllocator::default().next(), Uid::new(0));
+    }
+
+    #[test]
+    fn uid_try_new() {
+        assert_eq!(Uid::try_new(u32::MAX as u64).unwrap().get(), Uid::MAX);
+        assert_eq!(Uid::try_new(Uid::MAX + 1).unwrap_err(), Error::InvalidArg);
+    }
+}
@@ -0,0 +1,178 @@
+//! YAML conversions of [Value].
+
+use crate::types::data::{decode_base64, encode_base64};
+use crate::{Array, Data, Dictionary, Error, Integer, Null, PString, Real, Uid, Value};
+use serde_yaml_ng::value::{Mapping, Tag, TaggedValue};
+
+/// The tag of base64 encoded data strings.
+const BINARY_TAG: &str = "binary";
+/// The key of a mapping that represents a Uid.
+const UID_KEY: &str = "CF$UID";
+
+impl Value<'_> {
+    /// Exports the plist node as a YAML document.
+    ///
+    /// The nodes are mapped as follows:
+    ///
+    /// | Plist | YAML |
+    /// |-------|------|
+    /// | Dictionary | mapping |
+    /// | Array | sequence |
+    /// | Boolean, Integer, Real | scalar |
+    /// | String, Key | string |
+    /// | Data | base64 string tagged `!binary` |
+    /// | Date | RFC 3339 string in UTC, e.g. `2019-01-04T21:00:00Z` |
+    /// | Uid | mapping `{CF$UID: <integer>}` |
+    /// | Null | `null` |
+    ///
+    /// Dates become plain strings, so [from_yaml] reads them back as
+    /// strings. Returns [Error::Parse] if a string isn't valid UTF-8.
+    pub fn to_yaml(&self) -> Result<String, Error> {
+        serde_yaml_ng::to_string(&to_yaml_value(self)?).map_err(|_| Error::Format)
+    }
+}
+
+fn to_yaml_value(value: &Value) -> Result<serde_yaml_ng::Value, Error> {
+    Ok(match value {
+        Value::Dictionary(dict) => {
+            let mut mapping = Mapping::new();
+            for (key, item) in dict {
+                mapping.insert(key.into(), to_yaml_value(&item)?);
+            }
+            serde_yaml_ng::Value::Mapping(mapping)
+        }
+        Value::Array(array) => serde_yaml_ng::Value::Sequence(
+            array
+                .iter()
+                .map(|item| to_yaml_value(&item))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Boolean(boolean) => boolean.as_bool().into(),
+        Value::Integer(integer) if integer.is_unsigned() => integer.as_unsinged().into(),
+        Value::Integer(integer) => integer.as_singed().into(),
+        Value::Real(real) => real.as_float().into(),
+        Value::PString(string) => string.try_as_str()?.into(),
+        Value::Key(key) => key.get().into(),
+        Value::Data(data) => serde_yaml_ng::Value::Tagged(Box::new(TaggedValue {
+            tag: Tag::new(BINARY_TAG),
+            value: encode_base64(data.as_bytes()).into(),
+        })),
+        Value::Date(date) => date.to_rfc3339().into(),
+        Value::Uid(uid) => {
+            let mut mapping = Mapping::new();
+            mapping.insert(UID_KEY.into(), uid.get().into());
+            serde_yaml_ng::Value::Mapping(mapping)
+        }
+        Value::Null(_) => serde_yaml_ng::Value::Null,
+    })
+}
+
+/// Parses a YAML document and returns a [Value] struct representing a plist.
+///
+/// This is the inverse of [Value::to_yaml]: strings tagged `!binary` are
+/// decoded into data nodes and a mapping of a single `CF$UID` unsigned
+/// integer becomes a uid. Other tags are ignored. Mapping keys must be
+/// strings, numbers or booleans, which are converted to strings.
+///
+/// Returns [Error::Parse] if the document is malformed, a `!binary`
+/// string isn't valid base64, or a key is of another type.
+/// Returns [Error::InvalidArg] if a string contains an internal 0 byte.
+pub fn from_yaml<'a>(yaml: &str) -> Result<Value<'a>, Error> {
+    let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(yaml).map_err(|_| Error::Parse)?;
+    from_yaml_value(&value)
+}
+
+fn from_yaml_value<'a>(value: &serde_yaml_ng::Value) -> Result<Value<'a>, Error> {
+    Ok(match value {
+        serde_yaml_ng::Value::Null => Null::new().into(),
+        serde_yaml_ng::Value::Bool(boolean) => (*boolean).into(),
+        serde_yaml_ng::Value::Number(number) => {
+            if let Some(number) = number.as_i64() {
+                Integer::from(number).into()
+            } else if let Some(number) = number.as_u64() {
+                Integer::from(number).into()
+            } else {
+                Real::new(number.as_f64().ok_or(Error::Parse)?).into()
+            }
+        }
+        serde_yaml_ng::Value::String(string) => PString::try_new(string.as_str())?.into(),
+        serde_yaml_ng::Value::Sequence(sequence) => sequence
+            .iter()
+            .map(from_yaml_value)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .collect::<Array>()
+            .into(),
+        serde_yaml_ng::Value::Mapping(mapping) => {
+            if let [(serde_yaml_ng::Value::String(key), serde_yaml_ng::Value::Number(uid))] =
+                mapping.iter().collect::<Vec<_>>()[..]
+                && key == UID_KEY
+                && let Some(uid) = uid.as_u64()
+            {
+                return Ok(Uid::new(uid).into());
+            }
+            let mut dict = Dictionary::new();
+            for (key, item) in mapping {
+                let key = match key {
+                    serde_yaml_ng::Value::String(key) => key.clone(),
+                    serde_yaml_ng::Value::Number(key) => key.to_string(),
+                    serde_yaml_ng::Value::Bool(key) => key.to_string(),
+                    _ => return Err(Error::Parse),
+                };
+                if key.contains('\0') {
+                    return Err(Error::InvalidArg);
+                }
+                dict.insert(key, from_yaml_value(item)?);
+            }
+            dict.into()
+        }
+        serde_yaml_ng::Value::Tagged(tagged) => match (&tagged.value, tagged.tag == BINARY_TAG) {
+            (serde_yaml_ng::Value::String(encoded), true) => {
+                Data::new(&decode_base64(encoded).ok_or(Error::Parse)?).into()
+            }
+            _ => from_yaml_value(&tagged.value)?,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::time::Duration;
+
+    #[test]
+    fn yaml_round_trip() {
+        let plist: Value = dict!(
+            "name" => "pig",
+            "legs" => 4,
+            "big" => u64::MAX,
+            "height" => 0.9,
+            "pink" => true,
+            "bytes" => vec![0u8, 1, 254, 255],
+            "ref" => Uid::new(3),
+            "nothing" => Null::new(),
+            "list" => array!("a", dict!("b" => array!()))
+        )
+        .into();
+        let yaml = plist.to_yaml().unwrap();
+        assert!(yaml.contains("bytes: !binary AAH+/w=="), "{yaml}");
+        assert_eq!(from_yaml(&yaml).unwrap(), plist);
+    }
+
+    #[test]
+    fn yaml_date_is_lossy() {
+        let plist: Value = dict!("born" => Duration::from_secs(1546635600)).into();
+        let yaml = plist.to_yaml().unwrap();
+        let parsed = from_yaml(&yaml).unwrap();
+        let born = parsed.as_dictionary().unwrap().get("born").unwrap();
+        assert_eq!(born.as_string().unwrap().as_str(), "2019-01-04T21:00:00Z");
+    }
+
+    #[test]
+    fn yaml_errors() {
+        assert_eq!(from_yaml("a: [1, 2").unwrap_err(), Error::Parse);
+        assert_eq!(from_yaml("a: !binary '***'").unwrap_err(), Error::Parse);
+        assert_eq!(from_yaml("[1, 2]: x").unwrap_err(), Error::Parse);
+        assert_eq!(from_yaml("1: x").unwrap(), Value::from(dict!("1" => "x")));
+    }
+}
@@ -1,4 +1,4 @@
-use plist_plus2::{Uid, Value, array, dict};
+use plist_plus2::{Document, Error, PlistFile, PlistFormat, Uid, Value, ValueType, array, dict};
 use std::time::Duration;
 
 #[test]
@@ -126,6 +126,88 @@ fn binary() {
     assert!(dict == plist)
 }
 
+#[test]
+fn binary_from_reader() {
+    let file = std::fs::File::open("./tests/binary.plist").unwrap();
+    let plist = plist_plus2::from_reader(std::io::BufReader::new(file)).unwrap();
+    assert_eq!(
+        plist,
+        plist_plus2::from_file("./tests/binary.plist").unwrap()
+    );
+
+    struct Broken;
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::ConnectionReset.into())
+        }
+    }
+    assert_eq!(plist_plus2::from_reader(Broken).unwrap_err(), Error::IO);
+}
+
+#[test]
+fn binary_raw_format() {
+    let bytes = std::fs::read("./tests/binary.plist").unwrap();
+    let (plist, code) = plist_plus2::from_memory_raw_format(&bytes).unwrap();
+    assert_eq!(code, 2);
+    assert_eq!(PlistFormat::from_raw_code(code), Some(PlistFormat::Binary));
+    assert_eq!(plist, plist_plus2::from_memory(&bytes).unwrap());
+}
+
+#[test]
+fn binary_trailing_data() {
+    let mut bytes = std::fs::read("./tests/binary.plist").unwrap();
+    let plist = plist_plus2::from_memory(&bytes).unwrap();
+    bytes.extend_from_slice(b"EXTRA");
+    let (parsed, rest) = plist_plus2::from_memory_rest(&bytes).unwrap();
+    assert_eq!(parsed, plist);
+    assert_eq!(rest, b"EXTRA");
+}
+
+#[test]
+fn plist_file_save() {
+    let path = std::env::temp_dir().join("plist_plus2_plist_file_save.plist");
+    std::fs::copy("./tests/binary.plist", &path).unwrap();
+    let file = PlistFile::open(&path).unwrap();
+    assert_eq!(file.path(), path);
+
+    // Saving without a path writes back to the opened file
+    std::fs::remove_file(&path).unwrap();
+    file.save().unwrap();
+    let saved = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(saved.starts_with(b"bplist00"));
+    assert_eq!(&plist_plus2::from_memory(&saved).unwrap(), file.value());
+}
+
+#[test]
+fn binary_document_save() {
+    let mut doc = Document::from_file_with_format("./tests/binary.plist").unwrap();
+    assert_eq!(doc.format(), PlistFormat::Binary);
+    doc.value_mut()
+        .as_dictionary_mut()
+        .unwrap()
+        .insert("Author", "Christopher Marlowe");
+
+    let path = std::env::temp_dir().join("plist_plus2_binary_document_save.plist");
+    doc.save(&path).unwrap();
+
+    let saved = Document::from_file_with_format(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(saved.format(), PlistFormat::Binary);
+    assert_eq!(
+        saved
+            .value()
+            .as_dictionary()
+            .unwrap()
+            .get("Author")
+            .unwrap()
+            .as_string()
+            .unwrap()
+            .as_str(),
+        "Christopher Marlowe"
+    );
+}
+
 #[test]
 fn book() {
     let plist = plist_plus2::from_file("./tests/book.plist").unwrap();
@@ -138,6 +220,15 @@ fn book() {
     assert!(dict == plist)
 }
 
+#[cfg(feature = "toml")]
+#[test]
+fn book_to_toml() {
+    let plist = plist_plus2::from_file("./tests/book.plist").unwrap();
+    let toml = plist.to_toml().unwrap();
+    assert!(toml.contains("Title = \"Great Expectations\""), "{toml}");
+    assert!(toml.contains("CopiesSold = 123456789"), "{toml}");
+}
+
 #[test]
 fn utf16_bplist() {
     let plist = plist_plus2::from_file("./tests/utf16_bplist.plist").unwrap();
@@ -179,6 +270,33 @@ fn xml_animals() {
     assert!(dict == plist)
 }
 
+#[test]
+fn xml_animals_deep_contains() {
+    let plist = plist_plus2::from_file("./tests/xml-animals.plist").unwrap();
+    let smells: Value = dict!(
+        "lamb" => "lambish",
+        "pig" => "piggish",
+        "worm" => "wormy"
+    )
+    .into();
+    assert!(plist.deep_contains(&smells));
+    assert!(plist.deep_contains(&plist));
+    assert!(plist.deep_contains(&"oink".into()));
+    assert!(!plist.deep_contains(&"AnimalSounds".into()));
+    assert!(!plist.deep_contains(&dict!("lamb" => "lambish").into()));
+}
+
+#[test]
+fn xml_join_lines() {
+    let plist = plist_plus2::from_file("./tests/xml.plist").unwrap();
+    let lines = plist.as_dictionary().unwrap().get("Lines").unwrap();
+    let text = lines.as_array().unwrap().join_strings("\n").unwrap();
+    assert_eq!(
+        text,
+        "It is a tale told by an idiot,     \nFull of sound and fury, signifying nothing."
+    );
+}
+
 #[test]
 fn xml() {
     let plist = plist_plus2::from_file("./tests/xml.plist").unwrap();
@@ -196,10 +314,54 @@ fn xml() {
         "Blank" => "",
         "BiggestNumber" => u64::MAX,
         "SmallestNumber" => i64::MIN,
-        "HexademicalNumber" => 0xDEADBEEF as i64,
+        "HexademicalNumber" => 0xDEADBEEF_i64,
         "IsTrue" => true,
         "IsNotFalse" => false
     )
     .into();
     assert!(dict == plist)
 }
+
+#[test]
+fn binary_data_nodes() {
+    let plist = plist_plus2::from_file("./tests/binary.plist").unwrap();
+    let nodes: Vec<_> = plist.data_nodes().collect();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].0.to_string(), "Data");
+    assert_eq!(nodes[0].1.as_data().unwrap().len(), 15);
+
+    let plist = plist_plus2::from_file("./tests/binary_NSKeyedArchiver.plist").unwrap();
+    let nodes: Vec<_> = plist.data_nodes().collect();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].0.to_string(), "$objects/2/NS.data");
+    assert_eq!(nodes[0].1.as_data().unwrap().len(), 103);
+}
+
+#[test]
+fn xml_animals_replace_all() {
+    let mut plist = plist_plus2::from_file("./tests/xml-animals.plist").unwrap();
+    let (pink, rose) = (Value::from("pink"), Value::from("rose"));
+    assert_eq!(plist.replace_all(&pink, &rose), 2);
+    assert!(!plist.deep_contains(&pink));
+    assert_eq!(plist.replace_all(&pink, &rose), 0);
+    assert_eq!(plist.replace_all(&rose, &pink), 2);
+}
+
+#[test]
+fn book_probe() {
+    let plist = plist_plus2::from_file("./tests/book.plist").unwrap();
+    let book = plist.as_dictionary().unwrap();
+    assert_eq!(book.probe("Title"), Some(ValueType::String));
+    assert_eq!(book.probe("CopiesSold"), Some(ValueType::Integer));
+    assert_eq!(book.probe("Publisher"), None);
+    assert_eq!(book.probe("Ti\0tle"), None);
+}
+
+#[test]
+fn xml_error_position() {
+    let xml = std::fs::read_to_string("./tests/xml_error.plist").unwrap();
+    let Error::ParseAt { offset } = plist_plus2::from_xml(xml.as_str()).unwrap_err() else {
+        panic!("the position should be known");
+    };
+    assert!(xml[offset..].starts_with("</ke"), "{}", &xml[offset..]);
+}